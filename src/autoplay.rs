@@ -0,0 +1,90 @@
+use crate::{Card, CardSymbol, GameAction, GameData};
+
+/// The rank of `card` counting from 1 for an ace.
+fn rank(card: &Card) -> usize {
+    card.value as usize + 1
+}
+
+fn is_red(symbol: CardSymbol) -> bool {
+    matches!(symbol, CardSymbol::Hearts | CardSymbol::Diamonds)
+}
+
+impl GameData {
+    /// The first move of a card to a foundation that no other card still needs
+    /// to be put on, when drawing or on top of a playing stack.
+    pub(crate) fn safe_move(&self) -> Option<GameAction> {
+        self.foundation_move_of(|card| self.is_safe(card))
+    }
+
+    /// Like `safe_move`, but only for cards that can never be needed on the
+    /// playing stacks, so the solver does not need to try anything else.
+    pub(crate) fn provably_safe_move(&self) -> Option<GameAction> {
+        self.foundation_move_of(|card| self.is_provably_safe(card))
+    }
+
+    /// The first legal move to a foundation of a card that passes `test`.
+    fn foundation_move_of(&self, test: impl Fn(&Card) -> bool) -> Option<GameAction> {
+        let from_drawn = (0..4).map(GameAction::MoveToFoundationFromDrawn);
+        let from_playing =
+            (0..7).flat_map(|p| (0..4).map(move |f| GameAction::MoveToFoundationFromPlaying(p, f)));
+
+        from_drawn.chain(from_playing).find(|action| {
+            let card = match *action {
+                GameAction::MoveToFoundationFromDrawn(_) => self.tables.drawn_table.last(),
+                GameAction::MoveToFoundationFromPlaying(p, _) => {
+                    self.tables.playing_table[p].last()
+                }
+                _ => None,
+            };
+            self.is_legal(action).is_ok() && card.is_some_and(&test)
+        })
+    }
+
+    /// Whether `card` can go up without blocking a card of the other color: it
+    /// is at most 2 above the lowest foundation card of the other color.
+    fn is_safe(&self, card: &Card) -> bool {
+        rank(card)
+            <= self.lowest_foundation_rank(|symbol| is_red(symbol) != is_red(card.symbol)) + 2
+    }
+
+    /// Whether `card` can go up without ever being needed on the playing
+    /// stacks: it is at most 1 above the lowest foundation card of the other
+    /// color, or 2 above it while the other suit of its color is at most 3
+    /// below it.
+    fn is_provably_safe(&self, card: &Card) -> bool {
+        let lowest_other_color =
+            self.lowest_foundation_rank(|symbol| is_red(symbol) != is_red(card.symbol));
+        let same_color = self.lowest_foundation_rank(|symbol| {
+            is_red(symbol) == is_red(card.symbol) && symbol != card.symbol
+        });
+
+        let rank = rank(card);
+        rank <= lowest_other_color + 1 || (rank <= lowest_other_color + 2 && rank <= same_color + 3)
+    }
+
+    /// The lowest foundation rank of the suits that pass `test`.
+    fn lowest_foundation_rank(&self, test: impl Fn(CardSymbol) -> bool) -> usize {
+        [
+            CardSymbol::Hearts,
+            CardSymbol::Diamonds,
+            CardSymbol::Clubs,
+            CardSymbol::Spades,
+        ]
+        .into_iter()
+        .filter(|symbol| test(*symbol))
+        .map(|symbol| self.foundation_rank(symbol))
+        .min()
+        .unwrap_or(0)
+    }
+
+    /// The rank of the top card on the foundation of `symbol`, 0 when it is not
+    /// started.
+    fn foundation_rank(&self, symbol: CardSymbol) -> usize {
+        self.tables
+            .foundation_table
+            .iter()
+            .filter_map(|stack| stack.last())
+            .find(|card| card.symbol == symbol)
+            .map_or(0, rank)
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+/// The time spent playing a game. It starts with the first move and does not
+/// count while the game is paused.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Clock {
+    counted: Duration,              // the time played before running_since
+    running_since: Option<Instant>, // None before the first move and while paused
+    started: bool,
+}
+
+impl Clock {
+    /// A paused clock that has counted `elapsed`, e.g. for a game that is
+    /// picked up again.
+    pub fn paused_at(elapsed: Duration) -> Clock {
+        Clock {
+            counted: elapsed,
+            running_since: None,
+            started: true,
+        }
+    }
+
+    /// Starts counting, unless the clock was started before.
+    pub fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.counted += since.elapsed();
+        }
+    }
+
+    /// Counts on after a pause, a clock that was never started stays stopped.
+    pub fn resume(&mut self) {
+        if self.started && self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.started && self.running_since.is_none()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.counted + since.elapsed(),
+            None => self.counted,
+        }
+    }
+}
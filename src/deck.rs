@@ -4,6 +4,7 @@ use crossterm::style::Stylize;
 use rand::{thread_rng, Rng};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CardSymbol {
     Clubs,
     Diamonds,
@@ -14,15 +15,16 @@ pub enum CardSymbol {
 impl Display for CardSymbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CardSymbol::Clubs => write!(f, "{}", '\u{2663}'),
-            CardSymbol::Diamonds => write!(f, "{}", '\u{2666}'),
-            CardSymbol::Hearts => write!(f, "{}", '\u{2665}'),
-            CardSymbol::Spades => write!(f, "{}", '\u{2660}'),
+            CardSymbol::Clubs => write!(f, "\u{2663}"),
+            CardSymbol::Diamonds => write!(f, "\u{2666}"),
+            CardSymbol::Hearts => write!(f, "\u{2665}"),
+            CardSymbol::Spades => write!(f, "\u{2660}"),
         }
     }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CardValue {
     Ace,
     Two,
@@ -42,19 +44,19 @@ pub enum CardValue {
 impl Display for CardValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CardValue::Ace => write!(f, "{}", " A"),
-            CardValue::Two => write!(f, "{}", " 2"),
-            CardValue::Three => write!(f, "{}", " 3"),
-            CardValue::Four => write!(f, "{}", " 4"),
-            CardValue::Five => write!(f, "{}", " 5"),
-            CardValue::Six => write!(f, "{}", " 6"),
-            CardValue::Seven => write!(f, "{}", " 7"),
-            CardValue::Eight => write!(f, "{}", " 8"),
-            CardValue::Nine => write!(f, "{}", " 9"),
-            CardValue::Ten => write!(f, "{}", "10"),
-            CardValue::Jack => write!(f, "{}", " J"),
-            CardValue::Queen => write!(f, "{}", " Q"),
-            CardValue::King => write!(f, "{}", " K"),
+            CardValue::Ace => write!(f, " A"),
+            CardValue::Two => write!(f, " 2"),
+            CardValue::Three => write!(f, " 3"),
+            CardValue::Four => write!(f, " 4"),
+            CardValue::Five => write!(f, " 5"),
+            CardValue::Six => write!(f, " 6"),
+            CardValue::Seven => write!(f, " 7"),
+            CardValue::Eight => write!(f, " 8"),
+            CardValue::Nine => write!(f, " 9"),
+            CardValue::Ten => write!(f, "10"),
+            CardValue::Jack => write!(f, " J"),
+            CardValue::Queen => write!(f, " Q"),
+            CardValue::King => write!(f, " K"),
         }
     }
 }
@@ -83,25 +85,26 @@ const CARD_VALUES: [CardValue; 13] = [
 
 impl CardValue {
     pub fn is_followed_by(&self, other: CardValue) -> bool {
-        match (self, other) {
-            (CardValue::Ace, CardValue::Two) => true,
-            (CardValue::Two, CardValue::Three) => true,
-            (CardValue::Three, CardValue::Four) => true,
-            (CardValue::Four, CardValue::Five) => true,
-            (CardValue::Five, CardValue::Six) => true,
-            (CardValue::Six, CardValue::Seven) => true,
-            (CardValue::Seven, CardValue::Eight) => true,
-            (CardValue::Eight, CardValue::Nine) => true,
-            (CardValue::Nine, CardValue::Ten) => true,
-            (CardValue::Ten, CardValue::Jack) => true,
-            (CardValue::Jack, CardValue::Queen) => true,
-            (CardValue::Queen, CardValue::King) => true,
-            _ => false,
-        }
+        matches!(
+            (self, other),
+            (CardValue::Ace, CardValue::Two)
+                | (CardValue::Two, CardValue::Three)
+                | (CardValue::Three, CardValue::Four)
+                | (CardValue::Four, CardValue::Five)
+                | (CardValue::Five, CardValue::Six)
+                | (CardValue::Six, CardValue::Seven)
+                | (CardValue::Seven, CardValue::Eight)
+                | (CardValue::Eight, CardValue::Nine)
+                | (CardValue::Nine, CardValue::Ten)
+                | (CardValue::Ten, CardValue::Jack)
+                | (CardValue::Jack, CardValue::Queen)
+                | (CardValue::Queen, CardValue::King)
+        )
     }
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     pub value: CardValue,
     pub symbol: CardSymbol,
@@ -111,10 +114,6 @@ impl PartialEq for Card {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.value != other.value
-    }
 }
 impl PartialOrd for Card {
     fn ge(&self, other: &Self) -> bool {
@@ -135,11 +134,11 @@ impl PartialOrd for Card {
 
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         if self.lt(other) {
-            return Some(std::cmp::Ordering::Less);
+            Some(std::cmp::Ordering::Less)
         } else if self.gt(other) {
-            return Some(std::cmp::Ordering::Greater);
+            Some(std::cmp::Ordering::Greater)
         } else {
-            return Some(std::cmp::Ordering::Equal);
+            Some(std::cmp::Ordering::Equal)
         }
     }
 }
@@ -185,19 +184,29 @@ fn new_deck() -> Vec<Card> {
     deck
 }
 
-pub fn new_shuffled_deck(shuffle_times: i32) -> Vec<Card> {
+/// Shuffles a fresh deck with a Fisher-Yates shuffle, so every order of the
+/// deck is equally likely. A seeded generator always produces the same deck.
+pub fn shuffled_deck<R: Rng + ?Sized>(generator: &mut R) -> Vec<Card> {
     let mut deck = new_deck();
 
-    let mut generator = thread_rng();
-
-    for _ in 0..shuffle_times {
-        let pos_one = generator.gen_range(0..deck.len());
-        let pos_two = generator.gen_range(0..deck.len());
-
-        let temp = deck[pos_one];
-        deck[pos_one] = deck[pos_two];
-        deck[pos_two] = temp;
+    for i in (1..deck.len()).rev() {
+        // sample as u32, usize ranges draw differently on 32 and 64 bit platforms
+        let j = generator.gen_range(0..=i as u32) as usize;
+        deck.swap(i, j);
     }
 
     deck
 }
+
+#[deprecated(note = "`shuffle_times` is ignored, use `shuffled_deck` instead")]
+pub fn new_shuffled_deck(_shuffle_times: i32) -> Vec<Card> {
+    shuffled_deck(&mut thread_rng())
+}
+
+#[deprecated(note = "`shuffle_times` is ignored, use `shuffled_deck` instead")]
+pub fn new_shuffled_deck_with_rng<R: Rng + ?Sized>(
+    _shuffle_times: i32,
+    generator: &mut R,
+) -> Vec<Card> {
+    shuffled_deck(generator)
+}
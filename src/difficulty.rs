@@ -0,0 +1,88 @@
+use crate::solver::search;
+use crate::{Card, CardValue, GameData, Rules, Solution, WINNABLE_LIMITS};
+
+/// How hard a deal is, see `GameData::difficulty`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Difficulty {
+    pub solvable: Option<bool>, // None when the solver ran into its limits
+    pub first_solution_length: Option<usize>, // the moves of the first solution found, not the shortest
+    pub decisions: usize, // the positions on that solution with more than one move to try
+    pub search_nodes: usize, // the moves the solver tried
+    pub hidden_under_kings: usize, // the face down cards with a king on top of them
+}
+
+/// A rough grouping of solvable deals by `Difficulty::score`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DifficultyBand {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// A single number for how hard a solvable deal is, higher is harder.
+    pub fn score(&self) -> Option<usize> {
+        let length = self.first_solution_length?;
+        // the moves the solver had to take back weigh in, but a lot less
+        let backtracked = self.search_nodes.saturating_sub(length);
+        Some(length + self.decisions + 5 * self.hidden_under_kings + backtracked / 50)
+    }
+
+    /// The band of a solvable deal, None for deals the solver did not win.
+    pub fn band(&self) -> Option<DifficultyBand> {
+        match self.score()? {
+            0..=240 => Some(DifficultyBand::Easy),
+            241..=300 => Some(DifficultyBand::Medium),
+            _ => Some(DifficultyBand::Hard),
+        }
+    }
+}
+
+/// The face down cards below the highest king of a playing stack, they cannot
+/// be reached before the king has an empty stack to go to.
+fn hidden_under_king(stack: &[Card]) -> usize {
+    match stack.iter().rposition(|card| card.value == CardValue::King) {
+        Some(king) => stack[..king].iter().filter(|card| !card.is_face_up).count(),
+        None => 0,
+    }
+}
+
+impl GameData {
+    /// Rates the current game by solving it within `WINNABLE_LIMITS`.
+    pub fn difficulty(&self) -> Difficulty {
+        let found = search(&self.tables, &self.rules, &WINNABLE_LIMITS);
+        let (solvable, first_solution_length) = match found.solution {
+            Solution::Winnable(moves) => (Some(true), Some(moves.len())),
+            Solution::Unwinnable => (Some(false), None),
+            Solution::Unknown => (None, None),
+        };
+
+        let hidden_under_kings = self
+            .tables
+            .playing_table
+            .iter()
+            .map(|stack| hidden_under_king(stack))
+            .sum();
+
+        Difficulty {
+            solvable,
+            first_solution_length,
+            decisions: found.decisions,
+            search_nodes: found.nodes,
+            hidden_under_kings,
+        }
+    }
+
+    /// Deals the first game from `seed` on that falls in `band`, like
+    /// `GameData::new_winnable`.
+    pub fn new_with_difficulty(seed: u64, rules: Rules, band: DifficultyBand) -> GameData {
+        let mut seed = seed;
+        loop {
+            let game = GameData::new_with_rules(seed, rules);
+            if game.difficulty().band() == Some(band) {
+                return game;
+            }
+            seed = seed.wrapping_add(1);
+        }
+    }
+}
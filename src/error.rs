@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+use crate::Pile;
+
+/// The reason `GameData::do_` rejected a move.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    EmptySource,             // there is no card on the pile to move
+    EmptyFoundationNeedsAce, // only an ace can start a foundation stack
+    EmptyTableauNeedsKing,   // only a king can go on an empty playing stack
+    WrongSuit,               // foundation stacks hold a single suit
+    WrongRank,               // the card does not follow the card it goes on
+    SameColor,               // playing stacks alternate colors
+    FaceDownCard,            // only face up cards can be moved
+    SameStack,               // a stack cannot be moved onto itself
+    AlreadyFlipped,          // the card already faces the requested way
+    NoRedealsLeft,           // the stock was turned over as often as the rules allow
+    TakeBackOnly,            // the action only takes another move back, see `GameData::undo`
+
+    // the card index does not point at a (movable) card on the pile
+    IndexOutOfBounds { pile: Pile, index: usize },
+    NoSuchPile(Pile), // the stack number is not on the table
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::EmptySource => write!(f, "There is no card to move"),
+            MoveError::EmptyFoundationNeedsAce => {
+                write!(f, "Only an ace can go on an empty foundation stack")
+            }
+            MoveError::EmptyTableauNeedsKing => {
+                write!(f, "Only a king can go on an empty playing stack")
+            }
+            MoveError::WrongSuit => write!(f, "The card is not of the foundation's suit"),
+            MoveError::WrongRank => write!(f, "The card does not follow the card below it"),
+            MoveError::SameColor => write!(f, "The card has the same color as the card below it"),
+            MoveError::FaceDownCard => write!(f, "The card should be face up"),
+            MoveError::SameStack => write!(f, "Cannot move to the same stack"),
+            MoveError::AlreadyFlipped => write!(f, "The card is already turned that way"),
+            MoveError::NoRedealsLeft => write!(f, "The stock cannot be turned over again"),
+            MoveError::TakeBackOnly => write!(f, "This can only be done by undoing a move"),
+            MoveError::IndexOutOfBounds { pile, index } => {
+                write!(f, "There is no card with index {} on the {}", index, pile)
+            }
+            MoveError::NoSuchPile(pile) => write!(f, "There is no {}", pile),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// The reason a card or a board could not be read from its text notation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    Card(String),           // not a card such as `AC`, `TD` or `#KH`
+    Line(String),           // a board line that is not `pile: cards`, or a pile named twice
+    FoundationCount(usize), // a board needs exactly 4 foundation lines
+    TableauCount(usize),    // a board needs exactly 7 tableau lines
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Card(text) => write!(f, "`{}` is not a card", text),
+            ParseError::Line(line) => write!(f, "`{}` is not a line of a board", line),
+            ParseError::FoundationCount(count) => {
+                write!(f, "A board has 4 foundation stacks, not {}", count)
+            }
+            ParseError::TableauCount(count) => {
+                write!(f, "A board has 7 playing stacks, not {}", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,64 @@
+use crate::{CardValue, GameAction, GameData};
+
+impl GameData {
+    /// The best move to make next, see `GameData::hints`.
+    pub fn hint(&self) -> Option<GameAction> {
+        self.hints().into_iter().next()
+    }
+
+    /// The legal moves that get the game somewhere, best first: moves to the
+    /// foundations, moves that uncover a face down card, moves that empty a
+    /// stack for a king, the other moves and drawing last.
+    pub fn hints(&self) -> Vec<GameAction> {
+        let mut hints: Vec<(u8, GameAction)> = self
+            .legal_moves()
+            .filter(|action| *action == GameAction::DrawCard || self.is_useful(action))
+            .map(|action| (self.hint_rank(&action), action))
+            .collect();
+        hints.sort_by_key(|(rank, _)| *rank);
+
+        hints.into_iter().map(|(_, action)| action).collect()
+    }
+
+    /// Where a legal `action` goes in the hints, lower is better.
+    fn hint_rank(&self, action: &GameAction) -> u8 {
+        match *action {
+            GameAction::MoveToFoundationFromDrawn(_)
+            | GameAction::MoveToFoundationFromPlaying(_, _) => 0,
+            GameAction::MoveToPlayingFromPlaying(fs, 0, _, _) => {
+                let king_waiting = self.tables.playing_table[fs][0].value != CardValue::King
+                    && self.king_to_move();
+                if king_waiting {
+                    2
+                } else {
+                    3
+                }
+            }
+            GameAction::MoveToPlayingFromPlaying(fs, fc, _, _)
+                if !self.tables.playing_table[fs][fc - 1].is_face_up =>
+            {
+                1
+            }
+            GameAction::DrawCard => 4,
+            _ => 3,
+        }
+    }
+
+    /// Whether a king could move to an empty stack, from the drawn cards or
+    /// from the middle of a playing stack.
+    fn king_to_move(&self) -> bool {
+        let on_drawn = self
+            .tables
+            .drawn_table
+            .last()
+            .is_some_and(|card| card.value == CardValue::King);
+        let in_playing = self.tables.playing_table.iter().any(|stack| {
+            stack
+                .iter()
+                .skip(1)
+                .any(|card| card.is_face_up && card.value == CardValue::King)
+        });
+
+        on_drawn || in_playing
+    }
+}
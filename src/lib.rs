@@ -1,17 +1,85 @@
+mod autoplay;
+mod clock;
 mod deck;
-
-use deck::new_shuffled_deck;
+mod difficulty;
+mod error;
+mod hint;
+mod notation;
+mod replay;
+mod rules;
+#[cfg(feature = "serde")]
+mod save;
+mod scoring;
+mod solver;
+mod stats;
+
+use std::cmp::min;
+
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use scoring::{clamp_score, initial_score, score_change, time_bonus, time_penalty, undo_penalty};
+
+pub use clock::Clock;
+#[allow(deprecated)]
+pub use deck::new_shuffled_deck;
+#[allow(deprecated)]
+pub use deck::new_shuffled_deck_with_rng;
+pub use deck::shuffled_deck;
 pub use deck::Card;
 pub use deck::CardSymbol;
 pub use deck::CardValue;
-
+pub use difficulty::Difficulty;
+pub use difficulty::DifficultyBand;
+pub use error::MoveError;
+pub use error::ParseError;
+pub use replay::Replay;
+pub use rules::Rules;
+pub use rules::UndoPolicy;
+#[cfg(feature = "serde")]
+pub use save::LoadError;
+#[cfg(feature = "serde")]
+pub use save::SAVE_VERSION;
+pub use scoring::Scoring;
+pub use scoring::VEGAS_ANTE;
+pub use solver::solve;
+pub use solver::Solution;
+pub use solver::SolverLimits;
+pub use solver::WINNABLE_LIMITS;
+pub use stats::PlayerStats;
+pub use stats::Stats;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameTables {
     pub playing_table: [Vec<Card>; 7],
     pub foundation_table: [Vec<Card>; 4],
     pub extra_table: Vec<Card>,
     pub drawn_table: Vec<Card>,
+    pub redeals: usize, // how often the stock was turned over
+}
+
+/// A pile of cards on the table, used to point at where something went wrong.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pile {
+    Stock,             // the extra table
+    Waste,             // the drawn table
+    Foundation(usize), // the foundation stack
+    Tableau(usize),    // the playing stack
 }
 
+impl std::fmt::Display for Pile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pile::Stock => write!(f, "stock"),
+            Pile::Waste => write!(f, "waste"),
+            Pile::Foundation(stack) => write!(f, "foundation stack {}", stack),
+            Pile::Tableau(stack) => write!(f, "playing stack {}", stack),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameAction {
     DrawCard,
     UnDraw,
@@ -30,22 +98,133 @@ pub enum GameAction {
     HideCard(usize),
 }
 
+impl GameAction {
+    /// The action that takes this one back.
+    pub fn inverse(&self) -> GameAction {
+        match *self {
+            GameAction::DrawCard => GameAction::UnDraw,
+            GameAction::UnDraw => GameAction::DrawCard,
+
+            GameAction::MoveToPlayingFromFoundation(p, f) => {
+                GameAction::MoveToFoundationFromPlaying(p, f)
+            }
+            GameAction::MoveToFoundationFromPlaying(p, f) => {
+                GameAction::MoveToPlayingFromFoundation(p, f)
+            }
+
+            GameAction::MoveToPlayingFromDrawn(p) => GameAction::MoveToDrawnFromPlaying(p),
+            GameAction::MoveToDrawnFromPlaying(p) => GameAction::MoveToPlayingFromDrawn(p),
+
+            GameAction::MoveToDrawFromFoundation(f) => GameAction::MoveToFoundationFromDrawn(f),
+            GameAction::MoveToFoundationFromDrawn(f) => GameAction::MoveToDrawFromFoundation(f),
+
+            GameAction::MoveToPlayingFromPlaying(fs, fc, ts, tc) => {
+                GameAction::MoveToPlayingFromPlaying(ts, tc, fs, fc)
+            }
+
+            GameAction::ShowCard(p) => GameAction::HideCard(p),
+            GameAction::HideCard(p) => GameAction::ShowCard(p),
+        }
+    }
+}
+
+/// Where a game stands, see `GameData::status`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    InProgress,
+    Won,   // every card is on the foundation stacks
+    Stuck, // probably lost, no move that looks useful is left, see `GameData::status`
+}
+
+impl GameAction {
+    /// The playing stack a card is taken from, the card below it may be turned over.
+    fn playing_source(&self) -> Option<usize> {
+        match *self {
+            GameAction::MoveToFoundationFromPlaying(p, _)
+            | GameAction::MoveToDrawnFromPlaying(p)
+            | GameAction::MoveToPlayingFromPlaying(p, _, _, _) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+/// One player move as it is kept in the history, including the card it turned
+/// over.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
+    pub action: GameAction,
+    pub flipped: bool, // the action uncovered a face down card, which was turned face up
+    pub drawn: usize,  // how many cards a draw turned over, 0 when it turned the stock over
+    pub score: i32,    // how much the move changed the score
+    pub auto_moves: Vec<Move>, // the safe cards sent to the foundations right after it
+}
+
 pub struct GameData {
-    pub action_history: Vec<GameAction>,
+    pub action_history: Vec<Move>,
+    pub redo_history: Vec<Move>, // the undone moves, the last one is redone first
     pub tables: GameTables,
+    pub seed: u64, // the seed the deck was shuffled with, see GameData::new_with_seed
+    pub rules: Rules,
+    pub score: i32, // the points scored so far, stays 0 without scoring in the rules
+    pub clock: Clock,
+    pub timed_intervals: u64, // the 10 seconds of play a timed game was charged for
+    pub carried_score: i32,   // the part of the score brought over from earlier games
 }
 
-fn can_follow_foundation_card(this: &Card, other: &Card) -> bool {
-    this.symbol == other.symbol && other.value.is_followed_by(this.value)
+fn can_follow_foundation_card(this: &Card, other: &Card) -> Result<(), MoveError> {
+    if this.symbol != other.symbol {
+        return Err(MoveError::WrongSuit);
+    }
+    if !other.value.is_followed_by(this.value) {
+        return Err(MoveError::WrongRank);
+    }
+    Ok(())
 }
 
-fn can_follow_playing_card(this: &Card, other: &Card) -> bool {
-    this.get_color() != other.get_color() && this.value.is_followed_by(other.value)
+fn can_follow_playing_card(this: &Card, other: &Card) -> Result<(), MoveError> {
+    if this.get_color() == other.get_color() {
+        return Err(MoveError::SameColor);
+    }
+    if !this.value.is_followed_by(other.value) {
+        return Err(MoveError::WrongRank);
+    }
+    Ok(())
+}
+
+/// Whether `run` can be moved together: face up cards going down in
+/// alternating colors.
+fn can_move_together(run: &[Card]) -> Result<(), MoveError> {
+    // face up cards only fail that when every card was dealt face up
+    for pair in run.windows(2) {
+        if !pair[1].is_face_up {
+            return Err(MoveError::FaceDownCard);
+        }
+        can_follow_playing_card(&pair[1], &pair[0])?;
+    }
+    Ok(())
 }
 
 impl GameData {
-    pub fn new(shuffle_times: i32) -> GameData {
-        let mut deck = new_shuffled_deck(shuffle_times);
+    #[deprecated(note = "`shuffle_times` is ignored, use `GameData::new_with_seed` instead")]
+    pub fn new(_shuffle_times: i32) -> GameData {
+        GameData::new_with_seed(thread_rng().gen())
+    }
+
+    /// Deals the game identified by `seed`. The same seed always gives the same
+    /// tableau, stock and draw order, on every platform.
+    pub fn new_with_seed(seed: u64) -> GameData {
+        GameData::new_with_rules(seed, Rules::default())
+    }
+
+    /// Deals the game identified by `seed`, to be played by `rules`.
+    pub fn new_with_rules(seed: u64, rules: Rules) -> GameData {
+        let mut deck = shuffled_deck(&mut ChaCha8Rng::seed_from_u64(seed));
+        if rules.thoughtful {
+            for card in deck.iter_mut() {
+                card.is_face_up = true;
+            }
+        }
 
         let drawn_table: Vec<Card> = Vec::new();
         let foundation_table: [Vec<Card>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
@@ -66,401 +245,573 @@ impl GameData {
             stack[index].is_face_up = true;
         }
 
-        let extra_table = Vec::from(deck);
-        let action_history: Vec<GameAction> = Vec::new();
+        let extra_table = deck;
+        let action_history: Vec<Move> = Vec::new();
+        let redo_history: Vec<Move> = Vec::new();
 
         GameData {
             action_history,
+            redo_history,
             tables: GameTables {
                 playing_table,
                 foundation_table,
                 extra_table,
                 drawn_table,
+                redeals: 0,
             },
+            seed,
+            rules,
+            score: initial_score(&rules),
+            clock: Clock::default(),
+            timed_intervals: 0,
+            carried_score: 0,
         }
     }
 
-    pub fn do_(&mut self, action: GameAction) -> Result<GameAction, String> {
-        match action {
-            GameAction::DrawCard => self.draw(action),
-            GameAction::UnDraw => self.undraw(action),
+    /// Checks whether `action` can be played on the current tables, without
+    /// touching them.
+    pub fn is_legal(&self, action: &GameAction) -> Result<(), MoveError> {
+        match *action {
+            // these only take other moves back, undo plays them
+            GameAction::UnDraw
+            | GameAction::MoveToDrawnFromPlaying(_)
+            | GameAction::MoveToDrawFromFoundation(_)
+            | GameAction::ShowCard(_)
+            | GameAction::HideCard(_) => Err(MoveError::TakeBackOnly),
+
+            GameAction::DrawCard => {
+                if self.tables.extra_table.is_empty() && self.tables.drawn_table.is_empty() {
+                    return Err(MoveError::EmptySource);
+                }
+                if self.tables.extra_table.is_empty() && !self.can_redeal() {
+                    return Err(MoveError::NoRedealsLeft);
+                }
+                Ok(())
+            }
 
             GameAction::MoveToFoundationFromPlaying(p, f) => {
-                self.move_to_foundation_from_playing(p, f)
+                let card = self.top_playing_card(p)?;
+                if !card.is_face_up {
+                    return Err(MoveError::FaceDownCard);
+                }
+                self.can_go_on_foundation(card, f)
             }
             GameAction::MoveToPlayingFromFoundation(p, f) => {
-                self.move_to_playing_from_foundation(p, f)
+                let card = self.top_foundation_card(f)?;
+                self.can_go_on_playing(card, p)
             }
 
-            GameAction::MoveToPlayingFromDrawn(p) => self.move_to_playing_from_drawn(p),
-            GameAction::MoveToDrawnFromPlaying(p) => self.move_to_drawn_from_playing(p),
-
-            GameAction::MoveToDrawFromFoundation(f) => self.move_to_draw_from_foundation(f),
-            GameAction::MoveToFoundationFromDrawn(f) => self.move_to_foundation_from_drawn(f),
+            GameAction::MoveToPlayingFromDrawn(p) => {
+                let card = self.top_drawn_card()?;
+                self.can_go_on_playing(card, p)
+            }
+            GameAction::MoveToFoundationFromDrawn(f) => {
+                let card = self.top_drawn_card()?;
+                self.can_go_on_foundation(card, f)
+            }
 
             GameAction::MoveToPlayingFromPlaying(fs, fc, ts, tc) => {
-                self.move_to_playing_from_playing(fs, fc, ts, tc)
+                self.can_move_playing_to_playing(fs, fc, ts, tc)
             }
+        }
+    }
+
+    /// Every action the player can make right now, in a fixed order: drawing,
+    /// drawn to playing, drawn to foundation, playing to foundation, playing to
+    /// playing (every face up card with the cards on top of it) and foundation
+    /// to playing.
+    pub fn legal_moves(&self) -> impl Iterator<Item = GameAction> + '_ {
+        let tables = &self.tables;
+
+        let draw = std::iter::once(GameAction::DrawCard);
+        let from_drawn = (0..7)
+            .map(GameAction::MoveToPlayingFromDrawn)
+            .chain((0..4).map(GameAction::MoveToFoundationFromDrawn));
+        let playing_to_foundation =
+            (0..7).flat_map(|p| (0..4).map(move |f| GameAction::MoveToFoundationFromPlaying(p, f)));
+        let playing_to_playing = (0..7).flat_map(move |fs| {
+            (0..tables.playing_table[fs].len()).flat_map(move |fc| {
+                (0..7).map(move |ts| {
+                    GameAction::MoveToPlayingFromPlaying(fs, fc, ts, tables.playing_table[ts].len())
+                })
+            })
+        });
+        let foundation_to_playing =
+            (0..4).flat_map(|f| (0..7).map(move |p| GameAction::MoveToPlayingFromFoundation(p, f)));
+
+        draw.chain(from_drawn)
+            .chain(playing_to_foundation)
+            .chain(playing_to_playing)
+            .chain(foundation_to_playing)
+            .filter(move |action| self.is_legal(action).is_ok())
+    }
 
-            GameAction::ShowCard(p) => self.set_card_visibility(p, true),
-            GameAction::HideCard(p) => self.set_card_visibility(p, false),
+    /// Whether the game is won, still going or stuck. Stuck is a guess: moves
+    /// that only shift cards between playing stacks do not count, and moves
+    /// from the foundations only count when they free a face down card on the
+    /// next move, though a winning line may need a longer one.
+    pub fn status(&self) -> GameStatus {
+        if self.is_won() {
+            return GameStatus::Won;
+        }
+
+        let useful_move = self
+            .legal_moves()
+            .any(|action| action != GameAction::DrawCard && self.is_useful(&action));
+
+        if useful_move || self.useful_card_in_stock() {
+            GameStatus::InProgress
+        } else {
+            GameStatus::Stuck
         }
     }
 
-    pub fn undo(&mut self) {
-        let action = match self.action_history.pop() {
-            Some(a) => a,
-            None => return,
-        };
+    pub fn is_won(&self) -> bool {
+        self.tables
+            .foundation_table
+            .iter()
+            .all(|stack| stack.len() == 13)
+    }
+
+    /// The points scored in this game alone, without the ones carried over
+    /// from earlier games in cumulative Vegas.
+    pub fn game_score(&self) -> i32 {
+        self.score - self.carried_score
+    }
 
-        let _ = match action {
-            GameAction::DrawCard => self.undraw(action),
-            GameAction::UnDraw => self.draw(action),
+    /// How many moves the player made.
+    pub fn move_count(&self) -> usize {
+        self.action_history.len()
+    }
 
-            GameAction::MoveToFoundationFromPlaying(p, f) => {
-                self.move_to_playing_from_foundation(p, f)
+    /// Whether a legal `action` changes more than the order of the cards, e.g.
+    /// moving a king from one empty stack to another does not.
+    fn is_useful(&self, action: &GameAction) -> bool {
+        match *action {
+            GameAction::MoveToPlayingFromPlaying(fs, fc, ts, _) => {
+                let below = match fc.checked_sub(1) {
+                    Some(index) => self.tables.playing_table[fs][index],
+                    // emptying a stack is progress, unless it was a king that
+                    // moved to another empty stack
+                    None => return !self.tables.playing_table[ts].is_empty(),
+                };
+                // turning the card below over or putting it on the foundation is
+                // progress, moving between two equal cards is not
+                !below.is_face_up || (0..4).any(|f| self.can_go_on_foundation(&below, f).is_ok())
             }
             GameAction::MoveToPlayingFromFoundation(p, f) => {
-                self.move_to_foundation_from_playing(p, f)
+                let card = self.tables.foundation_table[f]
+                    .last()
+                    .expect("checked by is_legal");
+                // taking a card back is progress when the face up cards of
+                // another stack can then move onto it, off a face down card
+                (0..7).filter(|&s| s != p).any(|s| {
+                    let stack = &self.tables.playing_table[s];
+                    match stack.iter().position(|c| c.is_face_up) {
+                        Some(c) if c > 0 => {
+                            can_move_together(&stack[c..]).is_ok()
+                                && can_follow_playing_card(&stack[c], card).is_ok()
+                        }
+                        _ => false,
+                    }
+                })
             }
+            _ => true,
+        }
+    }
 
-            GameAction::MoveToPlayingFromDrawn(p) => self.move_to_drawn_from_playing(p),
-            GameAction::MoveToDrawnFromPlaying(p) => self.move_to_playing_from_drawn(p),
+    /// Whether drawing gets a card that can be played to the playing or
+    /// foundation stacks.
+    fn useful_card_in_stock(&self) -> bool {
+        self.reachable_drawn_cards().iter().any(|card| {
+            (0..4).any(|f| self.can_go_on_foundation(card, f).is_ok())
+                || (0..7).any(|p| self.can_go_on_playing(card, p).is_ok())
+        })
+    }
 
-            GameAction::MoveToDrawFromFoundation(f) => self.move_to_foundation_from_drawn(f),
-            GameAction::MoveToFoundationFromDrawn(f) => self.move_to_draw_from_foundation(f),
+    /// Whether the game is won by only moving cards to the foundations: every
+    /// card on the playing stacks is face up and the stock is gone.
+    pub fn can_auto_complete(&self) -> bool {
+        self.tables.extra_table.is_empty()
+            && self.tables.drawn_table.is_empty()
+            && self
+                .tables
+                .playing_table
+                .iter()
+                .all(|stack| stack.iter().all(|card| card.is_face_up))
+            && !self.is_won()
+    }
 
-            GameAction::MoveToPlayingFromPlaying(fs, fc, ts, tc) => {
-                self.force_to_playing_from_playing(ts, tc, fs, fc)
-            }
+    /// Plays the lowest card that can go on a foundation and records it in the
+    /// history, false when there is none.
+    pub fn auto_complete_step(&mut self) -> bool {
+        let next = (0..7)
+            .flat_map(|p| (0..4).map(move |f| GameAction::MoveToFoundationFromPlaying(p, f)))
+            .filter(|action| self.is_legal(action).is_ok())
+            .min_by_key(|action| match *action {
+                GameAction::MoveToFoundationFromPlaying(p, _) => {
+                    self.tables.playing_table[p].last().map(|card| card.value)
+                }
+                _ => None,
+            });
 
-            GameAction::ShowCard(p) => {
-                self.set_card_visibility(p, false).expect("");
-                self.undo();
-                Err(String::new())
+        match next.map(|action| self.do_(action)) {
+            Some(Ok(played)) => {
+                self.action_history.push(played);
+                true
             }
-            GameAction::HideCard(p) => {
-                self.set_card_visibility(p, true).expect("");
-                self.undo();
-                Err(String::new())
+            _ => false,
+        }
+    }
+
+    /// Moves every card it can to the foundations, one move at a time so each
+    /// one can be undone. Returns how many moves were played.
+    pub fn auto_complete(&mut self) -> usize {
+        let mut count = 0;
+        while self.auto_complete_step() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Whether the rules allow turning the stock over once more.
+    pub fn can_redeal(&self) -> bool {
+        match self.rules.max_redeals() {
+            Some(limit) => self.tables.redeals < limit,
+            None => true,
+        }
+    }
+
+    /// The cards that become the playable drawn card by only drawing, going
+    /// through the whole stock once more after turning it over.
+    fn reachable_drawn_cards(&self) -> Vec<Card> {
+        let mut extra_table = self.tables.extra_table.clone();
+        let mut drawn_table = self.tables.drawn_table.clone();
+        let mut reachable: Vec<Card> = drawn_table.last().copied().into_iter().collect();
+        let mut turned_over = false;
+
+        loop {
+            if extra_table.is_empty() {
+                if turned_over || drawn_table.is_empty() || !self.can_redeal() {
+                    return reachable;
+                }
+                turned_over = true;
+                std::mem::swap(&mut extra_table, &mut drawn_table);
+                extra_table.reverse();
+                continue;
             }
-        };
+
+            let count = min(self.rules.draw_count, extra_table.len());
+            drawn_table.extend(extra_table.drain(extra_table.len() - count..).rev());
+            reachable.push(*drawn_table.last().unwrap());
+        }
     }
 
-    fn move_to_foundation_from_drawn(
-        &mut self,
-        foundation_stack: usize,
-    ) -> Result<GameAction, String> {
-        if self.tables.drawn_table.len() == 0 {
-            return Err(String::from("Draw Stack is empty!"));
-        }
-
-        let contender_card = self
-            .tables
-            .drawn_table
-            .pop()
-            .expect("We check for non-zero length before");
-
-        if self.tables.foundation_table[foundation_stack].len() == 0 {
-            if contender_card.value == CardValue::Ace {
-                self.tables.foundation_table[foundation_stack].push(contender_card);
-                return Ok(GameAction::MoveToFoundationFromDrawn(foundation_stack));
-            } else {
-                self.tables.drawn_table.push(contender_card);
-                return Err(format!(
-                    "Card {} cannot go on foundation stack {}",
-                    contender_card, foundation_stack
-                ));
+    /// Plays `action` if it is legal and turns over the card it uncovers. A
+    /// rejected action leaves the tables untouched.
+    pub fn do_(&mut self, action: GameAction) -> Result<Move, MoveError> {
+        self.is_legal(&action)?;
+        self.clock.start();
+        self.tick();
+
+        let mut played = self.play(action);
+        if self.rules.auto_play_safe {
+            while let Some(safe) = self.safe_move() {
+                let auto_move = self.play(safe);
+                played.auto_moves.push(auto_move);
             }
         }
 
-        let receiver_card = self.tables.foundation_table[foundation_stack]
-            .get(self.tables.foundation_table[foundation_stack].len() - 1)
-            .expect("We check for non-zero length before");
+        self.redo_history.clear();
+        Ok(played)
+    }
+
+    /// Plays a legal `action`, turns over the card it uncovers and scores it.
+    fn play(&mut self, action: GameAction) -> Move {
+        let drawn = match action {
+            GameAction::DrawCard => min(self.rules.draw_count, self.tables.extra_table.len()),
+            _ => 0,
+        };
+        self.apply(&action);
+
+        let flipped = match action.playing_source() {
+            Some(p) if self.can_set_card_visibility(p, true).is_ok() => {
+                self.apply(&GameAction::ShowCard(p));
+                true
+            }
+            _ => false,
+        };
 
-        if !can_follow_foundation_card(&contender_card, receiver_card) {
-            self.tables.drawn_table.push(contender_card);
-            return Err(format!(
-                "Card {} cannot follow card {}",
-                contender_card, receiver_card
-            ));
+        let mut played = Move {
+            action,
+            flipped,
+            drawn,
+            score: 0,
+            auto_moves: Vec::new(),
+        };
+        played.score = score_change(&self.rules, self.score, &played);
+        if self.is_won() {
+            played.score += time_bonus(&self.rules, self.clock.elapsed().as_secs());
         }
+        self.score += played.score;
 
-        self.tables.foundation_table[foundation_stack].push(contender_card);
-        Ok(GameAction::MoveToFoundationFromDrawn(foundation_stack))
+        played
     }
 
-    fn move_to_draw_from_foundation(
-        &mut self,
-        foundation_stack: usize,
-    ) -> Result<GameAction, String> {
-        match self.tables.foundation_table[foundation_stack].pop() {
-            Some(c) => {
-                self.tables.drawn_table.push(c);
-                Ok(GameAction::MoveToDrawFromFoundation(foundation_stack))
-            }
-            None => Err(String::from("Illegal Move!")),
+    /// Charges a timed game for the time played since the last tick.
+    pub fn tick(&mut self) {
+        let intervals = self.clock.elapsed().as_secs() / 10;
+        if intervals > self.timed_intervals {
+            let penalty = intervals - self.timed_intervals;
+            self.score -= time_penalty(&self.rules, self.score, penalty);
+            self.timed_intervals = intervals;
         }
     }
-    fn draw(&mut self, action: GameAction) -> Result<GameAction, String> {
-        if self.tables.extra_table.is_empty() && self.tables.drawn_table.is_empty() {
-            return Ok(action);
+
+    /// Whether there is a move to take back that the rules allow taking back.
+    pub fn can_undo(&self) -> bool {
+        self.rules.undo != UndoPolicy::Disabled && !self.action_history.is_empty()
+    }
+
+    /// Takes back the last move, charging the undo penalty of the rules if
+    /// the game is scored.
+    pub fn undo(&mut self) {
+        if !self.can_undo() {
+            return;
         }
+        let played = self.action_history.pop().expect("checked with can_undo");
 
-        if self.tables.extra_table.is_empty() {
-            std::mem::swap(&mut self.tables.extra_table, &mut self.tables.drawn_table);
-            self.tables.extra_table.reverse();
-            for card in self.tables.extra_table.iter_mut() {
-                card.is_face_up = false;
-            }
-            return Ok(action);
-        } else {
-            let mut card = self.tables.extra_table.pop().unwrap();
-            card.is_face_up = true;
-            self.tables.drawn_table.push(card);
-            return Ok(action);
+        for auto_move in played.auto_moves.iter().rev() {
+            self.take_back(auto_move);
         }
+        self.take_back(&played);
+        self.score -= undo_penalty(&self.rules, self.score);
+        self.redo_history.push(played);
     }
 
-    fn undraw(&mut self, action: GameAction) -> Result<GameAction, String> {
-        if self.tables.extra_table.is_empty() && self.tables.drawn_table.is_empty() {
-            return Ok(action);
+    /// Reverses a single move, without the moves played automatically after it.
+    fn take_back(&mut self, played: &Move) {
+        if let (true, Some(p)) = (played.flipped, played.action.playing_source()) {
+            self.apply(&GameAction::HideCard(p));
         }
-
-        if self.tables.drawn_table.is_empty() {
-            std::mem::swap(&mut self.tables.extra_table, &mut self.tables.drawn_table);
-            self.tables.drawn_table.reverse();
-            for card in self.tables.drawn_table.iter_mut() {
-                card.is_face_up = true;
-            }
-            return Ok(action);
-        } else {
-            let mut card = self.tables.drawn_table.pop().unwrap();
-            card.is_face_up = false;
-            self.tables.extra_table.push(card);
-            return Ok(action);
+        match played.action {
+            GameAction::DrawCard => self.undraw(played.drawn),
+            _ => self.apply(&played.action.inverse()),
         }
+        self.score = clamp_score(&self.rules, self.score - played.score);
     }
 
-    fn move_to_foundation_from_playing(
-        &mut self,
-        playing_stack: usize,
-        foundation_stack: usize,
-    ) -> Result<GameAction, String> {
-        let contender_card = match self.tables.playing_table[playing_stack].pop() {
-            Some(x) => x,
-            None => return Err(String::from("Card stack is empty!")),
+    /// Plays the last undone move again, until a new action is done.
+    pub fn redo(&mut self) {
+        let played = match self.redo_history.pop() {
+            Some(m) => m,
+            None => return,
         };
 
-        if self.tables.foundation_table[foundation_stack].len() == 0 {
-            if contender_card.value == CardValue::Ace {
-                self.tables.foundation_table[foundation_stack].push(contender_card);
-                return Ok(GameAction::MoveToFoundationFromPlaying(
-                    playing_stack,
-                    foundation_stack,
-                ));
-            } else {
-                self.tables.playing_table[playing_stack].push(contender_card);
-                return Err(String::from("Foundation Stack is empty!"));
-            }
+        self.replay(&played);
+        for auto_move in played.auto_moves.iter() {
+            self.replay(auto_move);
+        }
+        self.action_history.push(played);
+    }
+
+    /// Plays a single move that was taken back again.
+    fn replay(&mut self, played: &Move) {
+        self.apply(&played.action);
+        if let (true, Some(p)) = (played.flipped, played.action.playing_source()) {
+            self.apply(&GameAction::ShowCard(p));
         }
+        self.score = clamp_score(&self.rules, self.score + played.score);
+    }
 
-        if let Some(card) = self.tables.foundation_table[foundation_stack]
-            .get(self.tables.foundation_table[foundation_stack].len() - 1)
-        {
-            if !can_follow_foundation_card(&contender_card, card) {
-                self.tables.playing_table[playing_stack].push(contender_card);
-                return Err(String::from("Invalid move!"));
+    /// Changes the tables for `action`, assuming it was checked with `is_legal`
+    /// (or is the inverse of an action that was, or turns over an uncovered
+    /// card).
+    fn apply(&mut self, action: &GameAction) {
+        let tables = &mut self.tables;
+
+        match *action {
+            GameAction::DrawCard => self.draw(self.rules.draw_count),
+            GameAction::UnDraw => self.undraw(1),
+
+            GameAction::MoveToFoundationFromPlaying(p, f) => {
+                let card = tables.playing_table[p].pop().expect("checked by is_legal");
+                tables.foundation_table[f].push(card);
+            }
+            GameAction::MoveToPlayingFromFoundation(p, f) => {
+                let card = tables.foundation_table[f]
+                    .pop()
+                    .expect("checked by is_legal");
+                tables.playing_table[p].push(card);
             }
-        };
 
-        // success! time to add the card
-        self.tables.foundation_table[foundation_stack].push(contender_card);
-        return Ok(GameAction::MoveToFoundationFromPlaying(
-            playing_stack,
-            foundation_stack,
-        ));
-    }
+            GameAction::MoveToPlayingFromDrawn(p) => {
+                let card = tables.drawn_table.pop().expect("checked by is_legal");
+                tables.playing_table[p].push(card);
+            }
+            GameAction::MoveToDrawnFromPlaying(p) => {
+                let card = tables.playing_table[p].pop().expect("checked by is_legal");
+                tables.drawn_table.push(card);
+            }
 
-    fn move_to_playing_from_foundation(
-        &mut self,
-        playing_stack: usize,
-        foundation_stack: usize,
-    ) -> Result<GameAction, String> {
-        let contender_card = match self.tables.foundation_table[foundation_stack].pop() {
-            Some(x) => x,
-            None => return Err(String::from("Card stack is empty!")),
-        };
+            GameAction::MoveToDrawFromFoundation(f) => {
+                let card = tables.foundation_table[f]
+                    .pop()
+                    .expect("checked by is_legal");
+                tables.drawn_table.push(card);
+            }
+            GameAction::MoveToFoundationFromDrawn(f) => {
+                let card = tables.drawn_table.pop().expect("checked by is_legal");
+                tables.foundation_table[f].push(card);
+            }
 
-        if let Some(card) = self.tables.playing_table[playing_stack]
-            .get(self.tables.playing_table[playing_stack].len() - 1)
-        {
-            if !can_follow_playing_card(&contender_card, card) {
-                self.tables.foundation_table[foundation_stack].push(contender_card);
-                return Err(String::from("Invalid Move!"));
+            GameAction::MoveToPlayingFromPlaying(fs, fc, ts, _) => {
+                let moved = tables.playing_table[fs].split_off(fc);
+                tables.playing_table[ts].extend(moved);
             }
-        };
 
-        // success! time to add the card
-        self.tables.playing_table[playing_stack].push(contender_card);
-        return Ok(GameAction::MoveToPlayingFromFoundation(
-            playing_stack,
-            foundation_stack,
-        ));
+            GameAction::ShowCard(p) => {
+                if let Some(card) = tables.playing_table[p].last_mut() {
+                    card.is_face_up = true;
+                }
+            }
+            GameAction::HideCard(p) => {
+                if let Some(card) = tables.playing_table[p].last_mut() {
+                    card.is_face_up = false;
+                }
+            }
+        }
     }
 
-    fn move_to_playing_from_drawn(&mut self, playing_stack: usize) -> Result<GameAction, String> {
-        let contender_card = match self.tables.drawn_table.pop() {
-            Some(card) => card,
-            None => return Err(String::from("Card stack is empty!")),
-        };
+    /// Turns over `count` cards, or fewer at the end of the stock. An empty stock
+    /// is turned over again from the drawn cards.
+    fn draw(&mut self, count: usize) {
+        if self.tables.extra_table.is_empty() {
+            std::mem::swap(&mut self.tables.extra_table, &mut self.tables.drawn_table);
+            self.tables.extra_table.reverse();
+            for card in self.tables.extra_table.iter_mut() {
+                card.is_face_up = self.rules.thoughtful;
+            }
+            self.tables.redeals += 1;
+        } else {
+            for _ in 0..min(count, self.tables.extra_table.len()) {
+                let mut card = self.tables.extra_table.pop().unwrap();
+                card.is_face_up = true;
+                self.tables.drawn_table.push(card);
+            }
+        }
+    }
 
-        if self.tables.playing_table[playing_stack].is_empty() {
-            if contender_card.value == CardValue::King {
-                self.tables.playing_table[playing_stack].push(contender_card);
-                return Ok(GameAction::MoveToPlayingFromDrawn(playing_stack));
-            } else {
-                self.tables.drawn_table.push(contender_card);
-                return Err(String::from("Illegal move"));
+    /// Puts `count` drawn cards back on the stock, or turns the drawn cards back
+    /// over if the stock was just turned over.
+    fn undraw(&mut self, count: usize) {
+        if self.tables.drawn_table.is_empty() {
+            std::mem::swap(&mut self.tables.extra_table, &mut self.tables.drawn_table);
+            self.tables.drawn_table.reverse();
+            for card in self.tables.drawn_table.iter_mut() {
+                card.is_face_up = true;
+            }
+            self.tables.redeals = self.tables.redeals.saturating_sub(1);
+        } else {
+            for _ in 0..count {
+                let mut card = self.tables.drawn_table.pop().unwrap();
+                card.is_face_up = self.rules.thoughtful;
+                self.tables.extra_table.push(card);
             }
         }
+    }
 
-        let card = self.tables.playing_table[playing_stack]
-            .get(self.tables.playing_table[playing_stack].len() - 1)
-            .unwrap();
+    fn playing_stack(&self, stack: usize) -> Result<&Vec<Card>, MoveError> {
+        self.tables
+            .playing_table
+            .get(stack)
+            .ok_or(MoveError::NoSuchPile(Pile::Tableau(stack)))
+    }
 
-        if !can_follow_playing_card(&contender_card, card) {
-            self.tables.drawn_table.push(contender_card);
-            return Err(String::from("Illegal move"));
-        }
+    fn foundation_stack(&self, stack: usize) -> Result<&Vec<Card>, MoveError> {
+        self.tables
+            .foundation_table
+            .get(stack)
+            .ok_or(MoveError::NoSuchPile(Pile::Foundation(stack)))
+    }
 
-        // now we are in the happy case
-        self.tables.playing_table[playing_stack].push(contender_card);
-        return Ok(GameAction::MoveToPlayingFromDrawn(playing_stack));
+    fn top_drawn_card(&self) -> Result<&Card, MoveError> {
+        self.tables.drawn_table.last().ok_or(MoveError::EmptySource)
     }
 
-    fn move_to_drawn_from_playing(&mut self, playing_stack: usize) -> Result<GameAction, String> {
-        let contender_card = match self.tables.playing_table[playing_stack].pop() {
-            Some(card) => card,
-            None => return Err(String::from("Card stack is empty!")),
-        };
+    fn top_playing_card(&self, stack: usize) -> Result<&Card, MoveError> {
+        self.playing_stack(stack)?
+            .last()
+            .ok_or(MoveError::EmptySource)
+    }
 
-        self.tables.drawn_table.push(contender_card);
-        return Ok(GameAction::MoveToDrawnFromPlaying(playing_stack));
+    fn top_foundation_card(&self, stack: usize) -> Result<&Card, MoveError> {
+        self.foundation_stack(stack)?
+            .last()
+            .ok_or(MoveError::EmptySource)
     }
 
-    fn force_to_playing_from_playing(
-        &mut self,
-        from_stack: usize,
-        from_card: usize,
-        to_stack: usize,
-        to_card: usize,
-    ) -> Result<GameAction, String> {
-        while self.tables.playing_table[from_stack].len() > from_card {
-            let to_transfer = self.tables.playing_table[from_stack].remove(from_card);
-            self.tables.playing_table[to_stack].push(to_transfer);
+    fn can_go_on_foundation(&self, card: &Card, foundation_stack: usize) -> Result<(), MoveError> {
+        match self.foundation_stack(foundation_stack)?.last() {
+            Some(receiver_card) => can_follow_foundation_card(card, receiver_card),
+            None if card.value == CardValue::Ace => Ok(()),
+            None => Err(MoveError::EmptyFoundationNeedsAce),
+        }
+    }
+
+    fn can_go_on_playing(&self, card: &Card, playing_stack: usize) -> Result<(), MoveError> {
+        match self.playing_stack(playing_stack)?.last() {
+            Some(receiver_card) => can_follow_playing_card(card, receiver_card),
+            None if card.value == CardValue::King => Ok(()),
+            None => Err(MoveError::EmptyTableauNeedsKing),
         }
-        Ok(GameAction::MoveToPlayingFromPlaying(
-            from_stack, from_card, to_stack, to_card,
-        ))
     }
-    fn move_to_playing_from_playing(
-        &mut self,
+
+    fn can_move_playing_to_playing(
+        &self,
         from_stack: usize,
         from_card: usize,
         to_stack: usize,
         to_card: usize,
-    ) -> Result<GameAction, String> {
+    ) -> Result<(), MoveError> {
         if from_stack == to_stack {
-            return Err(String::from("Cannot move to the same stack!"));
+            return Err(MoveError::SameStack);
         }
-        let _to_len = self.tables.playing_table[to_stack].len();
 
-        if to_card != self.tables.playing_table[to_stack].len() {
-            return Err(String::from(
-                "Illegal card indexes, to_card index should be {to_len}",
-            ));
+        if to_card != self.playing_stack(to_stack)?.len() {
+            return Err(MoveError::IndexOutOfBounds {
+                pile: Pile::Tableau(to_stack),
+                index: to_card,
+            });
         }
 
-        let contender_card = match self.tables.playing_table[from_stack].get(from_card) {
+        let contender_card = match self.playing_stack(from_stack)?.get(from_card) {
             Some(card) => card,
             None => {
-                return Err(String::from(
-                    "Illegal card indexes, from_card index out of bounds",
-                ))
+                return Err(MoveError::IndexOutOfBounds {
+                    pile: Pile::Tableau(from_stack),
+                    index: from_card,
+                })
             }
         };
 
         if !contender_card.is_face_up {
-            return Err(String::from("Card shold be face up!!"));
-        }
-
-        if self.tables.playing_table[to_stack].len() == 0 {
-            if contender_card.value == CardValue::King {
-                while self.tables.playing_table[from_stack].len() > from_card {
-                    let to_transfer = self.tables.playing_table[from_stack].remove(from_card);
-                    self.tables.playing_table[to_stack].push(to_transfer);
-                }
-                return Ok(GameAction::MoveToPlayingFromPlaying(
-                    from_stack, from_card, to_stack, to_card,
-                ));
-            } else {
-                return Err(String::from("Illegall Move!"));
-            }
+            return Err(MoveError::FaceDownCard);
         }
 
-        let receiver_card = match self.tables.playing_table[to_stack].get(to_card - 1) {
-            Some(card) => card,
-            None => {
-                return Err(format!(
-                    "Illegal move, card with index {} on stack {} does not exist!",
-                    to_card - 1,
-                    to_stack
-                ));
-            }
-        };
-
-        if !can_follow_playing_card(contender_card, receiver_card) {
-            return Err(format!(
-                "Illegal Move! {:?} cannot be on top of {:?}",
-                contender_card, receiver_card
-            ));
-        }
+        can_move_together(&self.tables.playing_table[from_stack][from_card..])?;
 
-        while self.tables.playing_table[from_stack].len() > from_card {
-            let to_transfer = self.tables.playing_table[from_stack].remove(from_card);
-            self.tables.playing_table[to_stack].push(to_transfer);
-        }
-        Ok(GameAction::MoveToPlayingFromPlaying(
-            from_stack, from_card, to_stack, to_card,
-        ))
+        self.can_go_on_playing(contender_card, to_stack)
     }
 
-    fn set_card_visibility(
-        &mut self,
+    fn can_set_card_visibility(
+        &self,
         playing_stack: usize,
         visible: bool,
-    ) -> Result<GameAction, String> {
-        let lenght = self.tables.playing_table[playing_stack].len();
-
-        if self.tables.playing_table[playing_stack].len() == 0 {
-            return Err(String::from("Stack is empty!!!"));
-        }
-        let mut card = match self.tables.playing_table[playing_stack].get_mut(lenght - 1) {
-            Some(c) => c,
-            None => return Err(String::from("Stack of cards empty!")),
-        };
-
-        if card.is_face_up == visible {
-            return Err(String::from("Card already visible {visibility}"));
-        }
-
-        card.is_face_up = visible;
-
-        if visible {
-            Ok(GameAction::ShowCard(playing_stack))
-        } else {
-            Ok(GameAction::HideCard(playing_stack))
+    ) -> Result<(), MoveError> {
+        if self.top_playing_card(playing_stack)?.is_face_up == visible {
+            return Err(MoveError::AlreadyFlipped);
         }
+        Ok(())
     }
 }
@@ -1,66 +1,187 @@
 use crossterm::{
     cursor::{Hide, MoveToPreviousLine},
-    event::{poll, read, Event, KeyCode},
+    event::{poll, read, Event, KeyCode, KeyModifiers},
     execute,
     style::{Print, Stylize},
-    terminal::{disable_raw_mode, enable_raw_mode},
+    terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
 };
-use solitaire::{Card, GameAction, GameData, GameTables};
+use solitaire::{
+    Card, DifficultyBand, GameAction, GameData, GameStatus, PlayerStats, Rules, Scoring, Solution,
+    SolverLimits, Stats, UndoPolicy,
+};
+#[cfg(feature = "serde")]
+use solitaire::{MoveError, Replay};
 use std::{
     cmp::{max, min},
-    io::stdout,
+    fs,
+    io::{self, stdout, Write},
+    path::PathBuf,
+    thread::sleep,
     time::Duration,
 };
 
-fn get_last_card_to_string(cards: &Vec<Card>, highlighted: bool) -> String {
-    if cards.len() == 0 {
-        get_card_nr_to_string(cards, 0, highlighted)
-    } else {
-        get_card_nr_to_string(cards, cards.len() - 1, highlighted)
-    }
+// the header, an empty line, 13 rows of cards and the status line
+const FRAME_HEIGHT: u16 = 18;
+
+/// How a spot on the table is drawn.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    No,
+    Cursor,
+    Hint, // where the hinted move goes
 }
 
-fn get_card_nr_to_string(cards: &Vec<Card>, i: usize, highlighted: bool) -> String {
-    if cards.len() <= i {
-        if highlighted {
-            return String::from("███");
+/// The spots to highlight, in the coordinates of the cursor: the header is row
+/// 0 and the playing stacks start at row 1.
+#[derive(Clone, Copy)]
+struct Marks {
+    cursor: Option<(usize, usize)>,
+    hint: Option<(usize, usize)>,
+}
+
+impl Marks {
+    fn cursor(x: usize, y: usize, hint: Option<(usize, usize)>) -> Marks {
+        Marks {
+            cursor: Some((x, y)),
+            hint,
+        }
+    }
+
+    fn none() -> Marks {
+        Marks {
+            cursor: None,
+            hint: None,
+        }
+    }
+
+    fn at(&self, x: usize, y: usize) -> Highlight {
+        if self.cursor == Some((x, y)) {
+            Highlight::Cursor
+        } else if self.hint == Some((x, y)) {
+            Highlight::Hint
         } else {
-            return String::from("   ");
+            Highlight::No
         }
     }
+}
 
-    if highlighted {
-        cards[i].to_string().on_white().black().to_string()
+fn get_last_card_to_string(cards: &[Card], highlight: Highlight) -> String {
+    if cards.is_empty() {
+        get_card_nr_to_string(cards, 0, highlight)
     } else {
-        cards[i].to_string()
+        get_card_nr_to_string(cards, cards.len() - 1, highlight)
     }
 }
 
-fn to_string(tables: &GameTables, x: usize, y: usize) -> String {
-    let header = format!(
-        "┌───┬───┬───┬───┐   ┌───┬───┐
-    \r│{}│{}│{}│{}│   │{}│{}│
-    \r└───┴───┴───┴───┘   └───┴───┘",
-        get_last_card_to_string(&tables.foundation_table[0], y == 0 && x == 0),
-        get_last_card_to_string(&tables.foundation_table[1], y == 0 && x == 1),
-        get_last_card_to_string(&tables.foundation_table[2], y == 0 && x == 2),
-        get_last_card_to_string(&tables.foundation_table[3], y == 0 && x == 3),
-        get_last_card_to_string(&tables.drawn_table, y == 0 && x == 5),
-        get_last_card_to_string(&tables.extra_table, y == 0 && x == 6)
-    );
+fn get_card_nr_to_string(cards: &[Card], i: usize, highlight: Highlight) -> String {
+    if cards.len() <= i {
+        return match highlight {
+            Highlight::No => String::from("   "),
+            Highlight::Cursor => String::from("███"),
+            Highlight::Hint => String::from("▒▒▒"),
+        };
+    }
+
+    match highlight {
+        Highlight::No => cards[i].to_string(),
+        Highlight::Cursor => cards[i].to_string().on_white().black().to_string(),
+        Highlight::Hint => cards[i].to_string().on_yellow().black().to_string(),
+    }
+}
+
+/// The last `count` drawn cards fanned out, only the top one can be highlighted.
+fn get_drawn_cards_to_string(cards: &[Card], count: usize, highlight: Highlight) -> String {
+    let fanned = min(count, cards.len());
+    let mut drawn = "   ".repeat(count - max(fanned, 1));
+
+    for i in cards.len() - fanned..cards.len().saturating_sub(1) {
+        drawn.push_str(&get_card_nr_to_string(cards, i, Highlight::No));
+    }
+    drawn.push_str(&get_last_card_to_string(cards, highlight));
+
+    drawn
+}
+
+/// The stock, crossed out once it is empty and cannot be turned over again.
+fn get_stock_to_string(game: &GameData, highlight: Highlight) -> String {
+    let tables = &game.tables;
+
+    if tables.extra_table.is_empty() && !tables.drawn_table.is_empty() && !game.can_redeal() {
+        return match highlight {
+            Highlight::No => String::from(" ╳ "),
+            Highlight::Cursor => " ╳ ".on_white().black().to_string(),
+            Highlight::Hint => " ╳ ".on_yellow().black().to_string(),
+        };
+    }
+
+    get_last_card_to_string(&tables.extra_table, highlight)
+}
+
+/// The stock in the order it is drawn, for games with every card face up.
+fn stock_order_to_string(cards: &[Card]) -> String {
+    let mut order = String::from("stock:");
+    for card in cards.iter().rev() {
+        order.push(' ');
+        order.push_str(&card.to_string());
+    }
+    order
+}
+
+fn time_to_string(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn header_to_string(game: &GameData, marks: Marks) -> String {
+    let tables = &game.tables;
+    let drawn_width = "───".repeat(game.rules.draw_count);
+    let time = time_to_string(game.clock.elapsed());
+    let score = match game.rules.scoring {
+        Scoring::None => String::new(),
+        Scoring::Standard => format!("  score: {}", game.score),
+        Scoring::Vegas { .. } => format!("  ${}", game.score),
+    };
+    let autoplay = if game.rules.auto_play_safe {
+        "  autoplay"
+    } else {
+        ""
+    };
+
+    format!(
+        "┌───┬───┬───┬───┐   ┌{}┬───┐
+    \r│{}│{}│{}│{}│   │{}│{}│  {}{}
+    \r└───┴───┴───┴───┘   └{}┴───┘{}",
+        drawn_width,
+        get_last_card_to_string(&tables.foundation_table[0], marks.at(0, 0)),
+        get_last_card_to_string(&tables.foundation_table[1], marks.at(1, 0)),
+        get_last_card_to_string(&tables.foundation_table[2], marks.at(2, 0)),
+        get_last_card_to_string(&tables.foundation_table[3], marks.at(3, 0)),
+        get_drawn_cards_to_string(&tables.drawn_table, game.rules.draw_count, marks.at(5, 0)),
+        get_stock_to_string(game, marks.at(6, 0)),
+        time,
+        score,
+        drawn_width,
+        autoplay,
+    )
+}
+
+/// The header and the playing stacks, everything but the status line.
+fn table_to_string(game: &GameData, marks: Marks) -> String {
+    let tables = &game.tables;
+    let header = header_to_string(game, marks);
 
     let mut body = String::new();
 
     for i in 0..=12 {
         let body_line = format!(
             " {} {} {} {} {} {} {}\n",
-            get_card_nr_to_string(&tables.playing_table[0], i, y == i + 1 && x == 0),
-            get_card_nr_to_string(&tables.playing_table[1], i, y == i + 1 && x == 1),
-            get_card_nr_to_string(&tables.playing_table[2], i, y == i + 1 && x == 2),
-            get_card_nr_to_string(&tables.playing_table[3], i, y == i + 1 && x == 3),
-            get_card_nr_to_string(&tables.playing_table[4], i, y == i + 1 && x == 4),
-            get_card_nr_to_string(&tables.playing_table[5], i, y == i + 1 && x == 5),
-            get_card_nr_to_string(&tables.playing_table[6], i, y == i + 1 && x == 6),
+            get_card_nr_to_string(&tables.playing_table[0], i, marks.at(0, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[1], i, marks.at(1, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[2], i, marks.at(2, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[3], i, marks.at(3, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[4], i, marks.at(4, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[5], i, marks.at(5, i + 1)),
+            get_card_nr_to_string(&tables.playing_table[6], i, marks.at(6, i + 1)),
         );
 
         body.push_str(&body_line);
@@ -69,38 +190,188 @@ fn to_string(tables: &GameTables, x: usize, y: usize) -> String {
     format!("{}\n\n{}", header, body)
 }
 
+fn to_string(game: &GameData, marks: Marks) -> String {
+    let tables = &game.tables;
+    let status_line = match game.status() {
+        GameStatus::Stuck => {
+            String::from("No useful moves seem left, the game is probably lost (n deals anew)")
+        }
+        _ if game.can_auto_complete() => {
+            String::from("Every card is face up, press a to finish the game")
+        }
+        _ if game.rules.thoughtful => stock_order_to_string(&tables.extra_table),
+        _ => String::new(),
+    };
+
+    format!("{}{}\n", table_to_string(game, marks), status_line)
+}
+
+/// The rules in a few words, e.g. "draw 3, 2 redeals, vegas".
+fn rules_to_string(rules: &Rules) -> String {
+    let mut parts = vec![format!("draw {}", rules.draw_count)];
+    parts.push(match rules.max_redeals() {
+        None => String::from("no redeal limit"),
+        Some(0) => String::from("no redeals"),
+        Some(1) => String::from("1 redeal"),
+        Some(limit) => format!("{} redeals", limit),
+    });
+    parts.push(String::from(match rules.scoring {
+        Scoring::None => "no scoring",
+        Scoring::Standard => "standard scoring",
+        Scoring::Vegas { cumulative: false } => "vegas",
+        Scoring::Vegas { cumulative: true } => "cumulative vegas",
+    }));
+    match rules.undo {
+        UndoPolicy::Allowed => (),
+        UndoPolicy::Disabled => parts.push(String::from("no undo")),
+        UndoPolicy::Penalty(points) => parts.push(format!("undo costs {}", points)),
+    }
+    if rules.timed {
+        parts.push(String::from("timed"));
+    }
+    if rules.thoughtful {
+        parts.push(String::from("thoughtful"));
+    }
+    parts.join(", ")
+}
+
+/// The lines of `stats` for games with `rules`.
+fn stats_lines(stats: &Stats, rules: &Rules) -> Vec<String> {
+    let win_rate = stats.win_rate().unwrap_or(0.0) * 100.0;
+    let mut lines = vec![
+        format!(
+            "played: {}  won: {} ({:.0}%)",
+            stats.played, stats.won, win_rate
+        ),
+        format!(
+            "streak: {}  best streak: {}",
+            stats.current_streak, stats.best_streak
+        ),
+    ];
+    if let (Some(fastest), Some(fewest)) = (stats.fastest_win, stats.fewest_moves) {
+        lines.push(format!(
+            "fastest win: {}  fewest moves: {}",
+            time_to_string(fastest),
+            fewest
+        ));
+    }
+    match (stats.best_score, rules.scoring) {
+        (Some(score), Scoring::Vegas { .. }) => lines.push(format!("best score: ${}", score)),
+        (Some(score), _) => lines.push(format!("best score: {}", score)),
+        (None, _) => (),
+    }
+    lines
+}
+
+/// The stats of the games played with the rules of `game`.
+fn stats_to_string(game: &GameData, stats: &PlayerStats) -> String {
+    let header = header_to_string(game, Marks::none());
+
+    let mut lines = vec![
+        String::new(),
+        format!("   {}", rules_to_string(&game.rules)),
+        String::new(),
+    ];
+    match stats.get(&game.rules) {
+        Some(stats) => lines.extend(
+            stats_lines(stats, &game.rules)
+                .into_iter()
+                .map(|line| format!("   {}", line)),
+        ),
+        None => lines.push(String::from("   No games finished with these rules yet")),
+    }
+    lines.push(String::new());
+    lines.push(String::from("   press any key to go back"));
+
+    let mut body = String::new();
+    for i in 0..=13 {
+        body.push_str(lines.get(i).map_or("", |line| line.as_str()));
+        body.push('\n');
+    }
+
+    format!("{}\n\n{}", header, body)
+}
+
+fn victory_to_string(game: &GameData) -> String {
+    let header = header_to_string(game, Marks::none());
+
+    let lines = [
+        String::new(),
+        String::from("   You won!"),
+        String::new(),
+        format!("   moves: {}", game.move_count()),
+        format!("   time:  {}", time_to_string(game.clock.elapsed())),
+        String::new(),
+        String::from("   press n for a new deal, t for stats or c to quit"),
+    ];
+
+    let mut body = String::new();
+    for i in 0..=13 {
+        body.push_str(lines.get(i).map_or("", |line| line.as_str()));
+        body.push('\n');
+    }
+
+    format!("{}\n\n{}", header, body)
+}
+
+/// The table with the cards hidden while the clock is paused.
+fn paused_to_string(game: &GameData) -> String {
+    let header = header_to_string(game, Marks::none());
+    let mut body = String::from("\n   Paused, press p to go on\n");
+    body.push_str(&"\n".repeat(12));
+
+    format!("{}\n\n{}", header, body)
+}
+
 fn display(table: String) {
-    execute!(stdout(), Hide, MoveToPreviousLine(17), Print(table),).unwrap();
+    execute!(
+        stdout(),
+        Hide,
+        MoveToPreviousLine(FRAME_HEIGHT),
+        Clear(ClearType::FromCursorDown),
+        Print(table),
+    )
+    .unwrap();
+}
+
+/// Plays the first legal action of `actions`, if there is one.
+fn play_first_legal(data: &mut GameData, actions: Vec<GameAction>) {
+    if let Some(action) = actions.into_iter().find(|a| data.is_legal(a).is_ok()) {
+        let played = data.do_(action).expect("checked with is_legal");
+        data.action_history.push(played);
+    }
+}
+
+/// Where the cursor goes for a hinted `action` and the spot it moves to.
+fn hint_spots(data: &GameData, action: &GameAction) -> Option<((usize, usize), (usize, usize))> {
+    // the top card of a playing stack, or the empty spot
+    let top = |stack: usize| (stack, max(data.tables.playing_table[stack].len(), 1));
+
+    match *action {
+        GameAction::DrawCard => Some(((6, 0), (5, 0))),
+        GameAction::MoveToPlayingFromDrawn(p) => Some(((5, 0), top(p))),
+        GameAction::MoveToFoundationFromDrawn(f) => Some(((5, 0), (f, 0))),
+        GameAction::MoveToFoundationFromPlaying(p, f) => Some((top(p), (f, 0))),
+        GameAction::MoveToPlayingFromPlaying(fs, fc, ts, _) => Some(((fs, fc + 1), top(ts))),
+        GameAction::MoveToPlayingFromFoundation(p, f) => Some(((f, 0), top(p))),
+        _ => None,
+    }
 }
 
 fn process_command(data: &mut GameData, x: usize, y: usize) {
     match (x, y) {
-        (4, 0) => return, // the empty spot just return
+        (4, 0) => (), // the empty spot, nothing to do
         (5, 0) => {
             // on the draw stack
-            for stack_index in 0..4 {
-                match data.do_(GameAction::MoveToFoundationFromDrawn(stack_index)) {
-                    Ok(a) => {
-                        data.action_history.push(a);
-                        return;
-                    }
-                    Err(_) => (),
-                }
-            }
-            for stack_index in 0..7 {
-                match data.do_(GameAction::MoveToPlayingFromDrawn(stack_index)) {
-                    Ok(a) => {
-                        data.action_history.push(a);
-                        return;
-                    }
-                    Err(_) => (),
-                };
-            }
+            let mut actions: Vec<GameAction> =
+                (0..4).map(GameAction::MoveToFoundationFromDrawn).collect();
+            actions.extend((0..7).map(GameAction::MoveToPlayingFromDrawn));
+
+            play_first_legal(data, actions);
         }
         (6, 0) => {
             // on the extra stack
-            data.do_(GameAction::DrawCard).expect("This shouldn't fail");
-            data.action_history.push(GameAction::DrawCard);
+            play_first_legal(data, vec![GameAction::DrawCard]);
         }
         (_, 0) => (), // the the cursor is on the foundation
         (_, _) => {
@@ -110,56 +381,520 @@ fn process_command(data: &mut GameData, x: usize, y: usize) {
                 return;
             }
 
-            if !data.tables.playing_table[x][y - 1].is_face_up {
-                return;
-            }
-
+            let mut actions = Vec::new();
             if y == data.tables.playing_table[x].len() {
-                for stack_index in 0..4 {
-                    match data.do_(GameAction::MoveToFoundationFromPlaying(x, stack_index)) {
-                        Ok(a) => {
-                            data.action_history.push(a);
-                            if let Ok(a) = data.do_(GameAction::ShowCard(x)) {
-                                data.action_history.push(a);
-                            }
-                            return;
-                        }
-                        Err(_) => (),
-                    }
-                }
+                actions
+                    .extend((0..4).map(|stack_index| {
+                        GameAction::MoveToFoundationFromPlaying(x, stack_index)
+                    }));
             }
-            for stack_idex in 0..7 {
-                match data.do_(GameAction::MoveToPlayingFromPlaying(
+            actions.extend((0..7).map(|stack_index| {
+                GameAction::MoveToPlayingFromPlaying(
                     x,
                     y - 1,
-                    stack_idex,
-                    data.tables.playing_table[stack_idex].len(),
-                )) {
-                    Ok(a) => {
-                        data.action_history.push(a);
-                        if let Ok(a) = data.do_(GameAction::ShowCard(x)) {
-                            data.action_history.push(a);
-                        }
-                        return;
+                    stack_index,
+                    data.tables.playing_table[stack_index].len(),
+                )
+            }));
+
+            play_first_legal(data, actions);
+        }
+    }
+}
+
+/// The rules picked on the command line. `--variant
+/// klondike|tournament|vegas|cumulative-vegas|thoughtful` starts from one of
+/// the `Rules` presets and the other options change it: `--draw 3` plays with
+/// three cards per draw, `--redeals 0` only allows one pass through the stock,
+/// `--scoring none|standard|vegas|cumulative-vegas` keeps score and `--undo
+/// on|off|<penalty>` sets whether moves can be taken back. `--timed` makes
+/// standard scores lose points over time, `--autoplay` sends safe cards to the
+/// foundations and `--thoughtful` deals every card face up.
+fn rules_from_args(args: &[String]) -> Rules {
+    let mut draw_count = 1;
+    if let Some(position) = args.iter().position(|arg| arg == "--draw") {
+        match args.get(position + 1).map(|count| count.as_str()) {
+            Some("1") => draw_count = 1,
+            Some("3") => draw_count = 3,
+            _ => usage_error("--draw takes 1 or 3"),
+        }
+    }
+
+    let mut rules = Rules {
+        draw_count,
+        ..Rules::default()
+    };
+    if let Some(position) = args.iter().position(|arg| arg == "--variant") {
+        match args.get(position + 1).map(|variant| variant.as_str()) {
+            Some("klondike") => (),
+            Some("tournament") => rules = Rules::tournament(draw_count),
+            Some("vegas") => rules = Rules::vegas(draw_count, false),
+            Some("cumulative-vegas") => rules = Rules::vegas(draw_count, true),
+            Some("thoughtful") => rules.thoughtful = true,
+            _ => usage_error(
+                "--variant takes klondike, tournament, vegas, cumulative-vegas or thoughtful",
+            ),
+        }
+    }
+
+    if let Some(position) = args.iter().position(|arg| arg == "--redeals") {
+        match args.get(position + 1).map(|limit| limit.parse()) {
+            Some(Ok(limit)) => rules.redeal_limit = Some(limit),
+            _ => usage_error("--redeals takes a number"),
+        }
+    }
+
+    if let Some(position) = args.iter().position(|arg| arg == "--scoring") {
+        match args.get(position + 1).map(|scoring| scoring.as_str()) {
+            Some("none") => rules.scoring = Scoring::None,
+            Some("standard") => rules.scoring = Scoring::Standard,
+            Some("vegas") => rules = vegas_rules(rules, false),
+            Some("cumulative-vegas") => rules = vegas_rules(rules, true),
+            _ => usage_error("--scoring takes none, standard, vegas or cumulative-vegas"),
+        }
+    }
+
+    rules.timed |= args.iter().any(|arg| arg == "--timed");
+    rules.auto_play_safe |= args.iter().any(|arg| arg == "--autoplay");
+    rules.thoughtful |= args.iter().any(|arg| arg == "--thoughtful");
+
+    if let Some(position) = args.iter().position(|arg| arg == "--undo") {
+        match args.get(position + 1).map(|undo| undo.as_str()) {
+            Some("on") => rules.undo = UndoPolicy::Allowed,
+            Some("off") => rules.undo = UndoPolicy::Disabled,
+            Some(penalty) => match penalty.parse() {
+                Ok(points) => rules.undo = UndoPolicy::Penalty(points),
+                Err(_) => usage_error("--undo takes on, off or a penalty in points"),
+            },
+            None => usage_error("--undo takes on, off or a penalty in points"),
+        }
+    }
+
+    rules
+}
+
+/// Which deals to play.
+#[derive(Clone, Copy)]
+enum DealKind {
+    Any,
+    Winnable,
+    Difficulty(DifficultyBand),
+}
+
+/// The deals picked on the command line, `--winnable` only deals games the
+/// solver can win and `--difficulty easy|medium|hard` only the ones that are
+/// that hard.
+fn deal_kind_from_args(args: &[String]) -> DealKind {
+    if let Some(position) = args.iter().position(|arg| arg == "--difficulty") {
+        match args.get(position + 1).map(|band| band.as_str()) {
+            Some("easy") => return DealKind::Difficulty(DifficultyBand::Easy),
+            Some("medium") => return DealKind::Difficulty(DifficultyBand::Medium),
+            Some("hard") => return DealKind::Difficulty(DifficultyBand::Hard),
+            _ => usage_error("--difficulty takes easy, medium or hard"),
+        }
+    }
+
+    if args.iter().any(|arg| arg == "--winnable") {
+        DealKind::Winnable
+    } else {
+        DealKind::Any
+    }
+}
+
+/// The seed picked with `--seed <seed>`, the deal is random without one.
+fn seed_from_args(args: &[String]) -> Option<u64> {
+    let position = args.iter().position(|arg| arg == "--seed")?;
+    match args.get(position + 1).map(|seed| seed.parse()) {
+        Some(Ok(seed)) => Some(seed),
+        _ => usage_error("--seed takes a number"),
+    }
+}
+
+/// Vegas rules for `rules`' draw count, keeping a redeal limit that was set.
+fn vegas_rules(rules: Rules, cumulative: bool) -> Rules {
+    Rules {
+        redeal_limit: rules.redeal_limit,
+        ..Rules::vegas(rules.draw_count, cumulative)
+    }
+}
+
+/// The directory the game keeps its files in, following the XDG base
+/// directory spec.
+fn data_dir() -> Option<PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(data_home.join("solitaire"))
+}
+
+/// The cumulative Vegas bankroll left by the previous games, 0 at first.
+fn load_bankroll() -> i32 {
+    data_dir()
+        .and_then(|dir| fs::read_to_string(dir.join("bankroll")).ok())
+        .and_then(|bankroll| bankroll.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn save_bankroll(bankroll: i32) -> io::Result<()> {
+    let dir = data_dir().ok_or(io::ErrorKind::NotFound)?;
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("bankroll"), format!("{}\n", bankroll))
+}
+
+fn is_cumulative(rules: &Rules) -> bool {
+    rules.scoring == Scoring::Vegas { cumulative: true }
+}
+
+/// The first deal from `seed` on that is of `kind`.
+fn new_game(rules: Rules, kind: DealKind, seed: u64) -> GameData {
+    match kind {
+        DealKind::Any => GameData::new_with_rules(seed, rules),
+        DealKind::Winnable => GameData::new_winnable(seed, rules),
+        DealKind::Difficulty(band) => GameData::new_with_difficulty(seed, rules, band),
+    }
+}
+
+/// Deals a new game, carrying the bankroll over in cumulative Vegas. With
+/// `kind` picks which deals are good enough.
+fn deal(rules: Rules, kind: DealKind, seed: u64) -> GameData {
+    let mut game = new_game(rules, kind, seed);
+    if is_cumulative(&rules) {
+        game.carried_score = load_bankroll();
+        game.score += game.carried_score;
+        // the ante is paid as soon as the cards are dealt
+        save_bankroll(game.score).ok();
+    }
+    game
+}
+
+/// Keeps an unfinished game for the next start, a finished one is thrown away.
+/// So is one without moves, unless its ante is already paid in cumulative Vegas.
+#[cfg(feature = "serde")]
+fn save_game(game: &GameData) -> io::Result<()> {
+    let dir = data_dir().ok_or(io::ErrorKind::NotFound)?;
+    let path = dir.join("savegame.json");
+
+    if game.is_won() || (game.move_count() == 0 && !is_cumulative(&game.rules)) {
+        return match fs::remove_file(path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        };
+    }
+    fs::create_dir_all(&dir)?;
+    fs::write(path, game.to_json())
+}
+
+#[cfg(feature = "serde")]
+fn load_game() -> Option<GameData> {
+    let json = fs::read_to_string(data_dir()?.join("savegame.json")).ok()?;
+    GameData::from_json(&json).ok()
+}
+
+/// Asks whether to go on with the game saved on the last quit, if there is
+/// one, and deals a new game from `seed` otherwise, so a saved game is never
+/// written over without asking.
+#[cfg(feature = "serde")]
+fn resume_or_deal(rules: Rules, kind: DealKind, seed: Option<u64>) -> GameData {
+    if let Some(mut saved) = load_game() {
+        println!("Resume the last game? (y/n)");
+        enable_raw_mode().unwrap();
+        let resume = loop {
+            if let Event::Key(key_event) = read().unwrap() {
+                match key_event.code {
+                    KeyCode::Char('y') | KeyCode::Enter => break true,
+                    KeyCode::Char('n') | KeyCode::Esc => break false,
+                    _ => (),
+                }
+            }
+        };
+        disable_raw_mode().unwrap();
+
+        if resume {
+            saved.clock.resume();
+            return saved;
+        }
+        record_game(&saved).ok();
+    }
+
+    deal(rules, kind, seed.unwrap_or_else(rand::random))
+}
+
+#[cfg(not(feature = "serde"))]
+fn resume_or_deal(rules: Rules, kind: DealKind, seed: Option<u64>) -> GameData {
+    deal(rules, kind, seed.unwrap_or_else(rand::random))
+}
+
+/// The stats of the games finished so far, none at first.
+#[cfg(feature = "serde")]
+fn load_stats() -> PlayerStats {
+    data_dir()
+        .and_then(|dir| fs::read_to_string(dir.join("stats.json")).ok())
+        .and_then(|json| PlayerStats::from_json(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_stats() -> PlayerStats {
+    PlayerStats::default()
+}
+
+/// Counts a game that was won or given up in the stats file.
+#[cfg(feature = "serde")]
+fn record_game(game: &GameData) -> io::Result<()> {
+    let dir = data_dir().ok_or(io::ErrorKind::NotFound)?;
+    let mut stats = load_stats();
+    stats.record(game);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("stats.json"), stats.to_json())
+}
+
+#[cfg(not(feature = "serde"))]
+fn record_game(_game: &GameData) -> io::Result<()> {
+    Ok(())
+}
+
+/// Keeps the moves of a game that was played in `replays/<seed>.json`, to be
+/// watched with `solitaire replay <file>`.
+#[cfg(feature = "serde")]
+fn save_replay(game: &GameData) -> io::Result<()> {
+    if game.move_count() == 0 {
+        return Ok(());
+    }
+    let dir = data_dir().ok_or(io::ErrorKind::NotFound)?.join("replays");
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(format!("{}.json", game.seed)),
+        game.to_replay().to_json(),
+    )
+}
+
+#[cfg(feature = "serde")]
+/// The time between two moves of a replay that plays by itself, `--speed
+/// <milliseconds>` on the command line.
+fn replay_delay_from_args(args: &[String]) -> Duration {
+    if let Some(position) = args.iter().position(|arg| arg == "--speed") {
+        match args.get(position + 1).map(|delay| delay.parse()) {
+            Some(Ok(delay)) => return Duration::from_millis(delay),
+            _ => usage_error("--speed takes the milliseconds between moves"),
+        }
+    }
+    Duration::from_millis(500)
+}
+
+#[cfg(feature = "serde")]
+fn replay_to_string(
+    game: &GameData,
+    replay: &Replay,
+    step: usize,
+    playing: bool,
+    delay: Duration,
+    error: Option<MoveError>,
+) -> String {
+    let status_line = match error {
+        Some(error) => format!("move {} cannot be played: {}", step + 1, error),
+        None => format!(
+            "move {}/{}  \u{2190} \u{2192} step, space {}, + - speed ({}ms), c quits",
+            step,
+            replay.actions.len(),
+            if playing { "stops" } else { "plays" },
+            delay.as_millis()
+        ),
+    };
+
+    format!("{}{}\n", table_to_string(game, Marks::none()), status_line)
+}
+
+#[cfg(feature = "serde")]
+/// Steps through `replay` with the arrow keys, or plays it one move every
+/// `delay`.
+fn watch_replay(replay: &Replay, mut delay: Duration) {
+    let mut game = replay.deal();
+    let mut step = 0;
+    let mut playing = false;
+    let mut error: Option<MoveError> = None;
+    let frame = |game: &GameData, step, playing, delay, error| {
+        replay_to_string(game, replay, step, playing, delay, error)
+    };
+    print!("{}", frame(&game, step, playing, delay, error));
+    display(frame(&game, step, playing, delay, error));
+
+    loop {
+        enable_raw_mode().unwrap();
+        let pressed = poll(delay).unwrap();
+        let event = if pressed { Some(read().unwrap()) } else { None };
+        disable_raw_mode().unwrap();
+
+        let forward = match event {
+            None if playing => true,
+            None => continue,
+            Some(Event::Key(key_event)) => match key_event.code {
+                KeyCode::Char('c') | KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Right => true,
+                KeyCode::Left => {
+                    if step > 0 {
+                        game.undo();
+                        step -= 1;
+                        error = None;
                     }
-                    Err(_) => (),
+                    false
+                }
+                KeyCode::Home => {
+                    game = replay.deal();
+                    step = 0;
+                    error = None;
+                    false
+                }
+                KeyCode::Char(' ') => {
+                    playing = !playing;
+                    false
+                }
+                KeyCode::Char('+') => {
+                    delay = max(delay / 2, Duration::from_millis(50));
+                    false
+                }
+                KeyCode::Char('-') => {
+                    delay = min(delay * 2, Duration::from_secs(5));
+                    false
+                }
+                _ => continue,
+            },
+            Some(_) => continue,
+        };
+
+        if forward && error.is_none() {
+            match replay.actions.get(step).map(|action| game.do_(*action)) {
+                Some(Ok(played)) => {
+                    game.action_history.push(played);
+                    step += 1;
                 }
+                Some(Err(move_error)) => error = Some(move_error),
+                None => (),
             }
         }
+        if step == replay.actions.len() || error.is_some() {
+            playing = false;
+        }
+
+        display(frame(&game, step, playing, delay, error));
+    }
+}
+
+/// `solitaire replay <file>` watches a replay written by `save_replay`.
+#[cfg(feature = "serde")]
+fn replay_command(args: &[String]) {
+    let path = match args.first() {
+        Some(path) => path,
+        None => usage_error("replay takes the file of the game to watch"),
+    };
+    let read = fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| Replay::from_json(&json).map_err(|error| error.to_string()));
+    match read {
+        Ok(replay) => watch_replay(&replay, replay_delay_from_args(args)),
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            std::process::exit(1);
+        }
     }
 }
-fn main() {
-    let mut game = GameData::new(10000);
+
+/// `solitaire solve <seed>` prints whether the deal can be won and how.
+fn solve_command(args: &[String]) {
+    let seed = match args.first().map(|seed| seed.parse()) {
+        Some(Ok(seed)) => seed,
+        _ => usage_error("solve takes the seed of the deal to solve"),
+    };
+    let game = GameData::new_with_rules(seed, rules_from_args(args));
+    let limits = SolverLimits::default();
+
+    match game.solve(&limits) {
+        Solution::Winnable(actions) => {
+            let mut moves = format!("winnable in {} moves\n", actions.len());
+            for action in actions {
+                moves.push_str(&format!("{:?}\n", action));
+            }
+            // a reader that stops early, like `head`, is not an error
+            stdout().write_all(moves.as_bytes()).ok();
+        }
+        Solution::Unwinnable => println!("unwinnable"),
+        Solution::Unknown => println!(
+            "unknown, the solver gave up at {} moves or {} seconds, whichever came first",
+            limits.max_nodes,
+            limits.max_time.as_secs()
+        ),
+    }
+}
+
+/// `solitaire deal` prints the seed of a deal, with `--print` the cards of
+/// the deal in the notation `GameTables` reads back instead.
+fn deal_command(args: &[String]) {
+    let seed = seed_from_args(args).unwrap_or_else(rand::random);
+    let game = new_game(rules_from_args(args), deal_kind_from_args(args), seed);
+
+    if args.iter().any(|arg| arg == "--print") {
+        print!("{}", game.tables);
+    } else {
+        println!("{}", game.seed);
+    }
+}
+
+/// `solitaire stats` prints the stats of every set of rules played so far.
+fn stats_command() {
+    let stats = load_stats();
+    if stats.by_rules.is_empty() {
+        println!("No games finished yet");
+    }
+    for (rules, stats) in &stats.by_rules {
+        println!("{}", rules_to_string(rules));
+        for line in stats_lines(stats, rules) {
+            println!("  {}", line);
+        }
+    }
+    println!("bankroll: ${}", load_bankroll());
+}
+
+/// Plays in the terminal until the player quits.
+fn play(args: &[String]) {
+    check_args(
+        args,
+        0,
+        &[RULE_OPTIONS, DEAL_OPTIONS].concat(),
+        &[RULE_FLAGS, DEAL_FLAGS].concat(),
+    );
+    let kind = deal_kind_from_args(args);
+    let mut game = resume_or_deal(rules_from_args(args), kind, seed_from_args(args));
+    // the next deals keep the rules of a resumed game
+    let mut rules = game.rules;
 
     let mut cursor_x: usize = 0;
     let mut cursor_y: usize = 0;
-    print!("{}", to_string(&game.tables, cursor_x, cursor_y));
-    display(to_string(&game.tables, cursor_x, cursor_y));
+    let mut hints: Vec<GameAction> = Vec::new();
+    let mut hint_index = 0;
+    let mut hint_target: Option<(usize, usize)> = None;
+    let mut stats_shown = false;
+    print!(
+        "{}",
+        to_string(&game, Marks::cursor(cursor_x, cursor_y, hint_target))
+    );
+    display(to_string(
+        &game,
+        Marks::cursor(cursor_x, cursor_y, hint_target),
+    ));
 
     loop {
         enable_raw_mode().unwrap();
 
         if !poll(Duration::from_millis(1_000)).unwrap() {
+            // keep the clock in the header going
+            if !game.clock.is_paused() && game.move_count() > 0 && !stats_shown {
+                game.tick();
+                disable_raw_mode().unwrap();
+                display(to_string(
+                    &game,
+                    Marks::cursor(cursor_x, cursor_y, hint_target),
+                ));
+            }
             continue;
         }
 
@@ -168,7 +903,46 @@ fn main() {
         disable_raw_mode().unwrap();
 
         if let Event::Key(key_event) = event {
+            let game_over = game.status() != GameStatus::InProgress;
+            let was_won = game.is_won();
+            if key_event.code != KeyCode::Char('h') {
+                hints.clear();
+                hint_target = None;
+            }
+
             match key_event.code {
+                _ if stats_shown => {
+                    // any key goes back to the game
+                    stats_shown = false;
+                    if !game.is_won() {
+                        game.clock.resume();
+                    }
+                }
+                KeyCode::Char('c') => break,
+                KeyCode::Char('n') if game_over => {
+                    if is_cumulative(&game.rules) {
+                        save_bankroll(game.score).ok();
+                    }
+                    if !game.is_won() {
+                        // the stuck game is given up
+                        record_game(&game).ok();
+                    }
+                    #[cfg(feature = "serde")]
+                    save_replay(&game).ok();
+                    game = deal(rules, kind, rand::random());
+                    cursor_x = 0;
+                    cursor_y = 0;
+                }
+                KeyCode::Char('t') if game.is_won() || !game.clock.is_paused() => {
+                    stats_shown = true;
+                    game.clock.pause();
+                }
+                _ if game.is_won() => continue, // only the victory screen is left
+
+                KeyCode::Char('p') if game.clock.is_paused() => game.clock.resume(),
+                KeyCode::Char('p') => game.clock.pause(),
+                _ if game.clock.is_paused() => continue,
+
                 KeyCode::Up => {
                     cursor_y = max(0, usize::saturating_sub(cursor_y, 1));
                 }
@@ -181,16 +955,169 @@ fn main() {
                 KeyCode::Right => {
                     cursor_x = min(6, cursor_x + 1);
                 }
-                KeyCode::Char('c') => break,
                 KeyCode::Enter => process_command(&mut game, cursor_x, cursor_y),
+                KeyCode::Char('a') if game.can_auto_complete() => {
+                    // the cards fly up one per frame
+                    while game.auto_complete_step() {
+                        display(to_string(&game, Marks::none()));
+                        sleep(Duration::from_millis(80));
+                    }
+                }
+                KeyCode::Char('s') => {
+                    // the setting stays for the next deals
+                    rules.auto_play_safe = !rules.auto_play_safe;
+                    game.rules.auto_play_safe = rules.auto_play_safe;
+                }
+                KeyCode::Char('h') => {
+                    // every press shows the next hint
+                    if hints.is_empty() {
+                        hints = game.hints();
+                        hint_index = 0;
+                    }
+                    if let Some((source, target)) = hints
+                        .get(hint_index % max(hints.len(), 1))
+                        .and_then(|action| hint_spots(&game, action))
+                    {
+                        (cursor_x, cursor_y) = source;
+                        hint_target = Some(target);
+                        hint_index += 1;
+                    }
+                }
                 KeyCode::Char('u') => game.undo(),
+                KeyCode::Char('r') => game.redo(),
+                KeyCode::Char('y') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    game.redo()
+                }
                 _ => continue,
             }
 
             // TODO add a help menu at the bottom of the game
             // TODO add undo button
 
-            display(to_string(&game.tables, cursor_x, cursor_y));
+            if game.is_won() && !was_won {
+                game.clock.pause();
+                record_game(&game).ok();
+            }
+
+            if stats_shown {
+                display(stats_to_string(&game, &load_stats()));
+            } else if game.is_won() {
+                display(victory_to_string(&game));
+            } else if game.clock.is_paused() {
+                display(paused_to_string(&game));
+            } else {
+                display(to_string(
+                    &game,
+                    Marks::cursor(cursor_x, cursor_y, hint_target),
+                ));
+            }
+        }
+    }
+
+    if is_cumulative(&game.rules) {
+        save_bankroll(game.score).ok();
+    }
+    #[cfg(feature = "serde")]
+    let kept = save_game(&game).is_ok();
+    #[cfg(not(feature = "serde"))]
+    let kept = false;
+    // a kept game counts once it is won or given up after resuming it, one
+    // that cannot be kept is given up now
+    if !kept && !game.is_won() && game.move_count() > 0 {
+        record_game(&game).ok();
+    }
+    #[cfg(feature = "serde")]
+    save_replay(&game).ok();
+}
+
+const USAGE: &str = "\
+usage: solitaire [play] [options]
+       solitaire solve <seed> [options]
+       solitaire deal [--seed <seed>] [--print] [options]
+       solitaire replay <file> [--speed <milliseconds>]
+       solitaire stats
+
+options:
+  --seed <seed>                 play the deal of this seed
+  --variant <variant>           klondike, tournament, vegas, cumulative-vegas or thoughtful
+  --draw 1|3                    cards turned over per draw
+  --redeals <count>             how often the stock can be turned over
+  --scoring <scoring>           none, standard, vegas or cumulative-vegas
+  --undo on|off|<penalty>       whether moves can be taken back, and at what cost
+  --timed                       standard scores lose points over time
+  --autoplay                    send safe cards to the foundations
+  --thoughtful                  deal every card face up
+  --winnable                    only deal games the solver can win
+  --difficulty easy|medium|hard only deal winnable games this hard
+";
+
+/// The options that set the rules and take a value, and the ones that don't.
+const RULE_OPTIONS: &[&str] = &["--variant", "--draw", "--redeals", "--scoring", "--undo"];
+const RULE_FLAGS: &[&str] = &["--timed", "--autoplay", "--thoughtful"];
+/// The options that pick the deal.
+const DEAL_OPTIONS: &[&str] = &["--seed", "--difficulty"];
+const DEAL_FLAGS: &[&str] = &["--winnable"];
+
+/// Stops with the usage on a command line that cannot be followed.
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+/// Refuses a command line with other arguments than `positionals` leading
+/// values, the `options` with their value and the `flags`.
+fn check_args(args: &[String], positionals: usize, options: &[&str], flags: &[&str]) {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if options.contains(&arg) {
+            if i + 1 == args.len() {
+                usage_error(&format!("{} takes a value", arg));
+            }
+            i += 2;
+        } else if flags.contains(&arg) || (i < positionals && !arg.starts_with('-')) {
+            i += 1;
+        } else {
+            usage_error(&format!("unknown argument `{}`", arg));
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(|command| command.as_str()) {
+        Some("-h") | Some("--help") | Some("help") => print!("{}", USAGE),
+        None => play(&args),
+        Some(option) if option.starts_with('-') => play(&args),
+        Some("play") => play(&args[1..]),
+        Some("solve") => {
+            check_args(&args[1..], 1, RULE_OPTIONS, RULE_FLAGS);
+            solve_command(&args[1..])
+        }
+        Some("deal") => {
+            check_args(
+                &args[1..],
+                0,
+                &[RULE_OPTIONS, DEAL_OPTIONS].concat(),
+                &[RULE_FLAGS, DEAL_FLAGS, &["--print"]].concat(),
+            );
+            deal_command(&args[1..])
+        }
+        #[cfg(feature = "serde")]
+        Some("replay") => {
+            check_args(&args[1..], 1, &["--speed"], &[]);
+            replay_command(&args[1..])
+        }
+        #[cfg(not(feature = "serde"))]
+        Some("replay") => {
+            eprintln!("replays need the serde feature");
+            std::process::exit(2);
+        }
+        Some("stats") => {
+            check_args(&args[1..], 0, &[], &[]);
+            stats_command()
         }
+        Some(command) => usage_error(&format!("unknown command `{}`", command)),
     }
 }
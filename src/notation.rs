@@ -0,0 +1,203 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{Card, CardSymbol, CardValue, GameTables, ParseError};
+
+/// Marks a face down card in the notation, as in `#KH`.
+const FACE_DOWN: char = '#';
+
+impl CardValue {
+    /// The value as a single character: `A`, `2` to `9`, `T`, `J`, `Q` or `K`.
+    pub fn to_notation(&self) -> char {
+        match self {
+            CardValue::Ace => 'A',
+            CardValue::Two => '2',
+            CardValue::Three => '3',
+            CardValue::Four => '4',
+            CardValue::Five => '5',
+            CardValue::Six => '6',
+            CardValue::Seven => '7',
+            CardValue::Eight => '8',
+            CardValue::Nine => '9',
+            CardValue::Ten => 'T',
+            CardValue::Jack => 'J',
+            CardValue::Queen => 'Q',
+            CardValue::King => 'K',
+        }
+    }
+}
+
+impl FromStr for CardValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(CardValue::Ace),
+            "2" => Ok(CardValue::Two),
+            "3" => Ok(CardValue::Three),
+            "4" => Ok(CardValue::Four),
+            "5" => Ok(CardValue::Five),
+            "6" => Ok(CardValue::Six),
+            "7" => Ok(CardValue::Seven),
+            "8" => Ok(CardValue::Eight),
+            "9" => Ok(CardValue::Nine),
+            "T" | "10" => Ok(CardValue::Ten),
+            "J" => Ok(CardValue::Jack),
+            "Q" => Ok(CardValue::Queen),
+            "K" => Ok(CardValue::King),
+            _ => Err(ParseError::Card(s.to_string())),
+        }
+    }
+}
+
+impl CardSymbol {
+    /// The suit as a single character: `C`, `D`, `H` or `S`.
+    pub fn to_notation(&self) -> char {
+        match self {
+            CardSymbol::Clubs => 'C',
+            CardSymbol::Diamonds => 'D',
+            CardSymbol::Hearts => 'H',
+            CardSymbol::Spades => 'S',
+        }
+    }
+}
+
+impl FromStr for CardSymbol {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "C" => Ok(CardSymbol::Clubs),
+            "D" => Ok(CardSymbol::Diamonds),
+            "H" => Ok(CardSymbol::Hearts),
+            "S" => Ok(CardSymbol::Spades),
+            _ => Err(ParseError::Card(s.to_string())),
+        }
+    }
+}
+
+impl Card {
+    /// The card as value and suit, `AC` or `TD`, with a leading `#` when it
+    /// is face down. `Card::from_str` reads it back.
+    pub fn to_notation(&self) -> String {
+        let card = format!("{}{}", self.value.to_notation(), self.symbol.to_notation());
+        if self.is_face_up {
+            card
+        } else {
+            format!("{}{}", FACE_DOWN, card)
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (is_face_up, card) = match s.strip_prefix(FACE_DOWN) {
+            Some(card) => (false, card),
+            None => (true, s),
+        };
+        let not_a_card = || ParseError::Card(s.to_string());
+
+        // the suit is always the last character, the value may be `10`
+        let split = card
+            .char_indices()
+            .last()
+            .map(|(index, _)| index)
+            .filter(|index| *index > 0)
+            .ok_or_else(not_a_card)?;
+        let (value, symbol) = card.split_at(split);
+
+        Ok(Card {
+            value: value.parse().map_err(|_| not_a_card())?,
+            symbol: symbol.parse().map_err(|_| not_a_card())?,
+            is_face_up,
+        })
+    }
+}
+
+fn write_pile(f: &mut std::fmt::Formatter<'_>, name: &str, cards: &[Card]) -> std::fmt::Result {
+    write!(f, "{}:", name)?;
+    for card in cards {
+        write!(f, " {}", card.to_notation())?;
+    }
+    writeln!(f)
+}
+
+/// The board as text, one pile per line with its cards from the bottom up:
+///
+/// ```text
+/// stock: #6C #7H #5S
+/// waste: 4D
+/// foundation: AS 2S
+/// tableau: #9C QH JS
+/// redeals: 0
+/// ```
+///
+/// There are 4 `foundation` and 7 `tableau` lines, in the order of the stacks.
+impl Display for GameTables {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_pile(f, "stock", &self.extra_table)?;
+        write_pile(f, "waste", &self.drawn_table)?;
+        for stack in &self.foundation_table {
+            write_pile(f, "foundation", stack)?;
+        }
+        for stack in &self.playing_table {
+            write_pile(f, "tableau", stack)?;
+        }
+        writeln!(f, "redeals: {}", self.redeals)
+    }
+}
+
+/// Reads the board written by `GameTables`' `Display`. Blank lines are
+/// skipped, and a missing stock, waste or redeals line means an empty pile or
+/// no redeals.
+impl FromStr for GameTables {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stock: Option<Vec<Card>> = None;
+        let mut waste: Option<Vec<Card>> = None;
+        let mut redeals: Option<usize> = None;
+        let mut foundations: Vec<Vec<Card>> = Vec::new();
+        let mut tableau: Vec<Vec<Card>> = Vec::new();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let bad_line = || ParseError::Line(line.to_string());
+            let (name, rest) = line.split_once(':').ok_or_else(bad_line)?;
+            let cards = || {
+                rest.split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<Vec<Card>, ParseError>>()
+            };
+
+            match name.trim() {
+                "stock" if stock.is_none() => stock = Some(cards()?),
+                "waste" if waste.is_none() => waste = Some(cards()?),
+                "foundation" => foundations.push(cards()?),
+                "tableau" => tableau.push(cards()?),
+                "redeals" if redeals.is_none() => {
+                    redeals = Some(rest.trim().parse().map_err(|_| bad_line())?)
+                }
+                _ => return Err(bad_line()),
+            }
+        }
+
+        let foundation_count = foundations.len();
+        let foundation_table = foundations
+            .try_into()
+            .map_err(|_| ParseError::FoundationCount(foundation_count))?;
+        let tableau_count = tableau.len();
+        let playing_table = tableau
+            .try_into()
+            .map_err(|_| ParseError::TableauCount(tableau_count))?;
+
+        Ok(GameTables {
+            playing_table,
+            foundation_table,
+            extra_table: stock.unwrap_or_default(),
+            drawn_table: waste.unwrap_or_default(),
+            redeals: redeals.unwrap_or(0),
+        })
+    }
+}
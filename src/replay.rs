@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crate::{Clock, GameAction, GameData, Move, MoveError, Rules, UndoPolicy};
+
+/// A game as the deal and the moves played on it, to watch it again or to
+/// play it back into `GameData` step by step.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules, // without safe autoplay, its moves are in `actions`
+    pub actions: Vec<GameAction>, // every move in the order it was played
+}
+
+impl Replay {
+    /// The game before its first move. Moves can always be undone in it, so a
+    /// replay can be stepped backward with `GameData::undo`. The time is not
+    /// recorded, the clock stays at 0.
+    pub fn deal(&self) -> GameData {
+        let mut game = GameData::new_with_rules(self.seed, self.rules);
+        game.rules.undo = UndoPolicy::Allowed;
+        game.clock = Clock::paused_at(Duration::ZERO);
+        game
+    }
+
+    /// The game after its first `step` moves, or the error of the first move
+    /// that could not be played.
+    pub fn game_at(&self, step: usize) -> Result<GameData, MoveError> {
+        let mut game = self.deal();
+        for action in self.actions.iter().take(step) {
+            let played = game.do_(*action)?;
+            game.action_history.push(played);
+        }
+        Ok(game)
+    }
+}
+
+impl GameData {
+    /// The moves played so far as a `Replay`. The moves safe autoplay made
+    /// are recorded as moves of their own.
+    pub fn to_replay(&self) -> Replay {
+        let actions = self
+            .action_history
+            .iter()
+            .flat_map(|played| std::iter::once(played).chain(played.auto_moves.iter()))
+            .map(|played: &Move| played.action)
+            .collect();
+
+        Replay {
+            seed: self.seed,
+            rules: Rules {
+                auto_play_safe: false,
+                ..self.rules
+            },
+            actions,
+        }
+    }
+}
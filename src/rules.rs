@@ -0,0 +1,84 @@
+use crate::Scoring;
+
+/// Whether a move can be taken back with `GameData::undo`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UndoPolicy {
+    Allowed,
+    Disabled,
+    Penalty(i32), // every undo costs this many points on top of reversing the move
+}
+
+/// The rule options a game is dealt with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rules {
+    pub draw_count: usize, // how many cards a draw turns over, only the top one is playable
+    pub redeal_limit: Option<usize>, // how often the stock can be turned over, None is no limit
+    pub scoring: Scoring,  // how the score is kept, if at all
+    pub undo: UndoPolicy,  // whether moves can be taken back
+    pub timed: bool,       // standard scores lose points over time and get a bonus at the win
+    pub auto_play_safe: bool, // cards that are no longer needed go to the foundations by themselves
+    pub thoughtful: bool,  // every card is dealt face up, the stock too
+}
+
+impl Rules {
+    /// Classic Klondike, turning over three cards at a time.
+    pub fn draw_three() -> Rules {
+        Rules {
+            draw_count: 3,
+            ..Rules::default()
+        }
+    }
+
+    /// Tournament rules, one pass through the stock when drawing one card or
+    /// three passes when drawing three.
+    pub fn tournament(draw_count: usize) -> Rules {
+        Rules {
+            draw_count,
+            redeal_limit: Some(if draw_count == 1 { 0 } else { 2 }),
+            ..Rules::default()
+        }
+    }
+
+    /// Vegas rules, tournament stock passes, betting on the cards that reach
+    /// the foundations and no taking moves back.
+    pub fn vegas(draw_count: usize, cumulative: bool) -> Rules {
+        Rules {
+            scoring: Scoring::Vegas { cumulative },
+            undo: UndoPolicy::Disabled,
+            ..Rules::tournament(draw_count)
+        }
+    }
+
+    /// Thoughtful Solitaire, Klondike with every card face up.
+    pub fn thoughtful() -> Rules {
+        Rules {
+            thoughtful: true,
+            ..Rules::default()
+        }
+    }
+
+    /// How often the stock can be turned over. Vegas scoring always limits the
+    /// passes, to the tournament limit when no other limit is set.
+    pub fn max_redeals(&self) -> Option<usize> {
+        match (self.redeal_limit, self.scoring) {
+            (None, Scoring::Vegas { .. }) => Rules::tournament(self.draw_count).redeal_limit,
+            (limit, _) => limit,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            draw_count: 1,
+            redeal_limit: None,
+            scoring: Scoring::None,
+            undo: UndoPolicy::Allowed,
+            timed: false,
+            auto_play_safe: false,
+            thoughtful: false,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Clock, GameAction, GameData, GameTables, Move, PlayerStats, Replay, Rules, Stats};
+
+/// The version of the formats `GameData::to_json`, `Replay::to_json` and
+/// `PlayerStats::to_json` write. Files of other versions are refused.
+pub const SAVE_VERSION: u32 = 1;
+
+/// The reason `GameData::from_json`, `Replay::from_json` or
+/// `PlayerStats::from_json` could not read a file.
+#[derive(Debug)]
+pub enum LoadError {
+    Json(serde_json::Error), // the file is not a saved game, replay or stats
+    Version(u32),            // the file was written in another version of the format
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Json(error) => write!(f, "The file cannot be read: {}", error),
+            LoadError::Version(version) => write!(
+                f,
+                "The file was written in version {} of the format, only version {} can be read",
+                version, SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(error: serde_json::Error) -> Self {
+        LoadError::Json(error)
+    }
+}
+
+/// Only the version, read first so other versions are refused before their
+/// fields get in the way.
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+/// Reads the version of `json`, refusing the ones that are not `SAVE_VERSION`.
+fn check_version(json: &str) -> Result<(), LoadError> {
+    let Version { version } = serde_json::from_str(json)?;
+    if version != SAVE_VERSION {
+        return Err(LoadError::Version(version));
+    }
+    Ok(())
+}
+
+/// Everything that makes up a game, as it is written to a file.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    version: u32,
+    seed: u64,
+    rules: Rules,
+    tables: GameTables,
+    action_history: Vec<Move>,
+    redo_history: Vec<Move>,
+    score: i32,
+    elapsed: Duration, // the time on the clock
+    timed_intervals: u64,
+    #[serde(default)] // not written before cumulative Vegas kept the game's own score
+    carried_score: i32,
+}
+
+impl GameData {
+    /// The whole game as pretty printed JSON, see `GameData::from_json`.
+    pub fn to_json(&self) -> String {
+        let saved = SavedGame {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            rules: self.rules,
+            tables: self.tables.clone(),
+            action_history: self.action_history.clone(),
+            redo_history: self.redo_history.clone(),
+            score: self.score,
+            elapsed: self.clock.elapsed(),
+            timed_intervals: self.timed_intervals,
+            carried_score: self.carried_score,
+        };
+
+        serde_json::to_string_pretty(&saved).expect("a game can always be written as JSON")
+    }
+
+    /// Reads a game written by `GameData::to_json`. A game that was started
+    /// comes back with its clock paused.
+    pub fn from_json(json: &str) -> Result<GameData, LoadError> {
+        check_version(json)?;
+        let saved: SavedGame = serde_json::from_str(json)?;
+
+        let clock = if saved.elapsed.is_zero() && saved.action_history.is_empty() {
+            Clock::default()
+        } else {
+            Clock::paused_at(saved.elapsed)
+        };
+
+        Ok(GameData {
+            action_history: saved.action_history,
+            redo_history: saved.redo_history,
+            tables: saved.tables,
+            seed: saved.seed,
+            rules: saved.rules,
+            score: saved.score,
+            clock,
+            timed_intervals: saved.timed_intervals,
+            carried_score: saved.carried_score,
+        })
+    }
+}
+
+/// A replay as it is written to a file.
+#[derive(Serialize, Deserialize)]
+struct ReplayFile {
+    version: u32,
+    seed: u64,
+    rules: Rules,
+    actions: Vec<GameAction>,
+}
+
+impl Replay {
+    /// The replay as pretty printed JSON, see `Replay::from_json`.
+    pub fn to_json(&self) -> String {
+        let file = ReplayFile {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            rules: self.rules,
+            actions: self.actions.clone(),
+        };
+
+        serde_json::to_string_pretty(&file).expect("a replay can always be written as JSON")
+    }
+
+    /// Reads a replay written by `Replay::to_json`.
+    pub fn from_json(json: &str) -> Result<Replay, LoadError> {
+        check_version(json)?;
+        let file: ReplayFile = serde_json::from_str(json)?;
+
+        Ok(Replay {
+            seed: file.seed,
+            rules: file.rules,
+            actions: file.actions,
+        })
+    }
+}
+
+/// The stats of one set of rules as they are written to a file.
+#[derive(Serialize, Deserialize)]
+struct RulesStats {
+    rules: Rules,
+    stats: Stats,
+}
+
+/// The player's stats as they are written to a file.
+#[derive(Serialize, Deserialize)]
+struct StatsFile {
+    version: u32,
+    by_rules: Vec<RulesStats>,
+}
+
+impl PlayerStats {
+    /// The stats as pretty printed JSON, see `PlayerStats::from_json`.
+    pub fn to_json(&self) -> String {
+        let file = StatsFile {
+            version: SAVE_VERSION,
+            by_rules: self
+                .by_rules
+                .iter()
+                .map(|&(rules, stats)| RulesStats { rules, stats })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&file).expect("stats can always be written as JSON")
+    }
+
+    /// Reads stats written by `PlayerStats::to_json`.
+    pub fn from_json(json: &str) -> Result<PlayerStats, LoadError> {
+        check_version(json)?;
+        let file: StatsFile = serde_json::from_str(json)?;
+
+        Ok(PlayerStats {
+            by_rules: file
+                .by_rules
+                .into_iter()
+                .map(|entry| (entry.rules, entry.stats))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{GameAction, Move, Rules, UndoPolicy};
+
+/// How a game is scored, set in the `Rules`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scoring {
+    None,
+    Standard, // the usual Windows Klondike points
+
+    // paying $52 a deal and winning $5 a card on the foundations, cumulative
+    // games carry the money over to the next deal
+    Vegas { cumulative: bool },
+}
+
+/// The price of a deal in Vegas scoring.
+pub const VEGAS_ANTE: i32 = 52;
+
+/// The points a move earns with standard scoring.
+fn standard_points(rules: &Rules, played: &Move) -> i32 {
+    let points = match played.action {
+        GameAction::MoveToPlayingFromDrawn(_) => 5,
+        GameAction::MoveToFoundationFromDrawn(_)
+        | GameAction::MoveToFoundationFromPlaying(_, _) => 10,
+        GameAction::MoveToPlayingFromFoundation(_, _) => -15,
+        GameAction::ShowCard(_) => 5,
+        GameAction::HideCard(_) => -5,
+        // turning the stock over when drawing one card at a time
+        GameAction::DrawCard if played.drawn == 0 && rules.draw_count == 1 => -100,
+        _ => 0,
+    };
+
+    if played.flipped {
+        points + 5
+    } else {
+        points
+    }
+}
+
+/// The money a move wins or loses with Vegas scoring.
+fn vegas_points(played: &Move) -> i32 {
+    match played.action {
+        GameAction::MoveToFoundationFromDrawn(_)
+        | GameAction::MoveToFoundationFromPlaying(_, _) => 5,
+        GameAction::MoveToPlayingFromFoundation(_, _) | GameAction::MoveToDrawFromFoundation(_) => {
+            -5
+        }
+        _ => 0,
+    }
+}
+
+/// The score a new deal starts with.
+pub(crate) fn initial_score(rules: &Rules) -> i32 {
+    match rules.scoring {
+        Scoring::Vegas { .. } => -VEGAS_ANTE,
+        _ => 0,
+    }
+}
+
+/// How much `played` changes `score`. Standard scores do not go below zero,
+/// Vegas scores can.
+pub(crate) fn score_change(rules: &Rules, score: i32, played: &Move) -> i32 {
+    match rules.scoring {
+        Scoring::None => 0,
+        Scoring::Standard => {
+            let points = standard_points(rules, played);
+            (score + points).max(0) - score
+        }
+        Scoring::Vegas { .. } => vegas_points(played),
+    }
+}
+
+/// `score` kept at zero or above for standard scoring, for changes that are
+/// not a move played now, like taking one back or playing it again.
+pub(crate) fn clamp_score(rules: &Rules, score: i32) -> i32 {
+    match rules.scoring {
+        Scoring::Standard => score.max(0),
+        _ => score,
+    }
+}
+
+/// How many points an undo costs at `score`, nothing when the game is not
+/// scored.
+pub(crate) fn undo_penalty(rules: &Rules, score: i32) -> i32 {
+    match (rules.undo, rules.scoring) {
+        (UndoPolicy::Penalty(points), Scoring::Standard) => points.min(score.max(0)),
+        (UndoPolicy::Penalty(points), Scoring::Vegas { .. }) => points,
+        _ => 0,
+    }
+}
+
+/// The bonus for winning a timed standard game after `seconds`, games won in
+/// less than 30 seconds get none.
+pub(crate) fn time_bonus(rules: &Rules, seconds: u64) -> i32 {
+    if rules.scoring != Scoring::Standard || !rules.timed || seconds < 30 {
+        return 0;
+    }
+    (700_000 / seconds) as i32
+}
+
+/// How much a timed standard game loses at `score` for `intervals` more 10
+/// seconds of play, 2 points each.
+pub(crate) fn time_penalty(rules: &Rules, score: i32, intervals: u64) -> i32 {
+    if rules.scoring != Scoring::Standard || !rules.timed {
+        return 0;
+    }
+    (2 * intervals as i32).min(score.max(0))
+}
@@ -0,0 +1,211 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::{Card, Clock, GameAction, GameData, GameTables, Move, Rules};
+
+/// What the solver found out about a deal.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Solution {
+    Winnable(Vec<GameAction>), // the moves that win the game, in order
+    Unwinnable,
+    Unknown, // the search ran into its limits
+}
+
+/// How far the solver searches before giving up with `Solution::Unknown`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SolverLimits {
+    pub max_nodes: usize, // the moves tried, counting the ones that were taken back
+    pub max_time: Duration,
+}
+
+impl Default for SolverLimits {
+    fn default() -> Self {
+        SolverLimits {
+            max_nodes: 1_000_000,
+            max_time: Duration::from_secs(10),
+        }
+    }
+}
+
+/// The search budget for `GameData::new_winnable`, without a time limit so the
+/// same seed always gives the same deal.
+pub const WINNABLE_LIMITS: SolverLimits = SolverLimits {
+    max_nodes: 100_000,
+    max_time: Duration::MAX,
+};
+
+/// The moves the search tries at a node.
+struct Frame {
+    moves: Vec<GameAction>,
+    next: usize,
+}
+
+/// Decides whether `tables` can be won by `rules`, knowing where the face down
+/// cards are. Searches depth first and never visits a position twice.
+pub fn solve(tables: &GameTables, rules: &Rules, limits: &SolverLimits) -> Solution {
+    search(tables, rules, limits).solution
+}
+
+/// What a search found and how it got there.
+pub(crate) struct Search {
+    pub solution: Solution,
+    pub nodes: usize,     // the moves tried
+    pub decisions: usize, // the positions on the way to the win with more than one move to try
+}
+
+pub(crate) fn search(tables: &GameTables, rules: &Rules, limits: &SolverLimits) -> Search {
+    let started = Instant::now();
+    let mut game = GameData {
+        action_history: Vec::new(),
+        redo_history: Vec::new(),
+        tables: tables.clone(),
+        seed: 0,
+        // only the rules that change the moves matter
+        rules: Rules {
+            draw_count: rules.draw_count,
+            redeal_limit: rules.max_redeals(),
+            thoughtful: rules.thoughtful,
+            ..Rules::default()
+        },
+        score: 0,
+        clock: Clock::default(),
+        timed_intervals: 0,
+        carried_score: 0,
+    };
+    if game.is_won() {
+        return Search {
+            solution: Solution::Winnable(Vec::new()),
+            nodes: 0,
+            decisions: 0,
+        };
+    }
+
+    let mut seen: HashSet<u64> = HashSet::new();
+    seen.insert(game.state_hash());
+    let mut path: Vec<Move> = Vec::new();
+    let mut stack = vec![Frame {
+        moves: game.solver_moves(),
+        next: 0,
+    }];
+    let mut nodes = 0;
+
+    while let Some(frame) = stack.last_mut() {
+        let action = match frame.moves.get(frame.next) {
+            Some(action) => *action,
+            None => {
+                // every move from here was tried, back to the position before
+                stack.pop();
+                if let Some(played) = path.pop() {
+                    game.take_back(&played);
+                }
+                continue;
+            }
+        };
+        frame.next += 1;
+
+        nodes += 1;
+        if nodes > limits.max_nodes || (nodes % 1024 == 0 && started.elapsed() > limits.max_time) {
+            return Search {
+                solution: Solution::Unknown,
+                nodes,
+                decisions: 0,
+            };
+        }
+
+        let played = game.play(action);
+        if !seen.insert(game.state_hash()) {
+            game.take_back(&played);
+            continue;
+        }
+        path.push(played);
+        if game.is_won() {
+            return Search {
+                solution: Solution::Winnable(path.iter().map(|played| played.action).collect()),
+                nodes,
+                decisions: stack.iter().filter(|frame| frame.moves.len() > 1).count(),
+            };
+        }
+
+        stack.push(Frame {
+            moves: game.solver_moves(),
+            next: 0,
+        });
+    }
+
+    Search {
+        solution: Solution::Unwinnable,
+        nodes,
+        decisions: 0,
+    }
+}
+
+/// A number for a card that tells face up and face down cards apart.
+fn card_code(card: &Card) -> u8 {
+    let code = card.symbol as u8 * 13 + card.value as u8;
+    if card.is_face_up {
+        code + 64
+    } else {
+        code
+    }
+}
+
+impl GameData {
+    /// Deals the first game from `seed` on that the solver wins within
+    /// `WINNABLE_LIMITS`. The seed of the deal is kept in `GameData::seed`.
+    pub fn new_winnable(seed: u64, rules: Rules) -> GameData {
+        let mut seed = seed;
+        loop {
+            let game = GameData::new_with_rules(seed, rules);
+            if let Solution::Winnable(_) = game.solve(&WINNABLE_LIMITS) {
+                return game;
+            }
+            seed = seed.wrapping_add(1);
+        }
+    }
+
+    /// Solves the current game with its rules, see `solve`.
+    pub fn solve(&self, limits: &SolverLimits) -> Solution {
+        solve(&self.tables, &self.rules, limits)
+    }
+
+    /// A hash of the position that is the same however the playing stacks and
+    /// foundation stacks are ordered. The stock turn-overs only count when
+    /// they are limited.
+    pub fn state_hash(&self) -> u64 {
+        let tables = &self.tables;
+        let code_stack = |stack: &Vec<Card>| stack.iter().map(card_code).collect::<Vec<u8>>();
+
+        let mut playing: Vec<Vec<u8>> = tables.playing_table.iter().map(code_stack).collect();
+        playing.sort();
+        let mut foundation: Vec<Vec<u8>> = tables.foundation_table.iter().map(code_stack).collect();
+        foundation.sort();
+
+        let mut hasher = DefaultHasher::new();
+        playing.hash(&mut hasher);
+        foundation.hash(&mut hasher);
+        code_stack(&tables.extra_table).hash(&mut hasher);
+        code_stack(&tables.drawn_table).hash(&mut hasher);
+        if self.rules.max_redeals().is_some() {
+            tables.redeals.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// The moves worth trying, best first. A provably safe move to a
+    /// foundation is the only one tried when there is one.
+    fn solver_moves(&self) -> Vec<GameAction> {
+        if let Some(safe) = self.provably_safe_move() {
+            return vec![safe];
+        }
+
+        let mut moves = self.hints();
+        let rest: Vec<GameAction> = self
+            .legal_moves()
+            .filter(|action| !moves.contains(action))
+            .collect();
+        moves.extend(rest);
+        moves
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use crate::{GameData, Rules, Scoring};
+
+/// The record of the games played with one set of rules.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32, // the games won in a row up to the last one
+    pub best_streak: u32,
+    pub fastest_win: Option<Duration>,
+    pub fewest_moves: Option<usize>, // the moves of the shortest win
+    pub best_score: Option<i32>,     // the best `GameData::game_score`, None without scoring
+}
+
+impl Stats {
+    /// The share of the games played that were won, None before the first.
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.played == 0 {
+            None
+        } else {
+            Some(self.won as f64 / self.played as f64)
+        }
+    }
+
+    /// Counts `game` as a finished game, won or given up.
+    pub fn record(&mut self, game: &GameData) {
+        self.played += 1;
+
+        if game.is_won() {
+            self.won += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+
+            let time = game.clock.elapsed();
+            self.fastest_win = Some(self.fastest_win.map_or(time, |fastest| fastest.min(time)));
+            let moves = game.move_count();
+            self.fewest_moves = Some(self.fewest_moves.map_or(moves, |fewest| fewest.min(moves)));
+        } else {
+            self.current_streak = 0;
+        }
+
+        if game.rules.scoring != Scoring::None {
+            let score = game.game_score();
+            self.best_score = Some(self.best_score.map_or(score, |best| best.max(score)));
+        }
+    }
+}
+
+/// The `Stats` of every set of rules that was played, in the order they were
+/// first played.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct PlayerStats {
+    pub by_rules: Vec<(Rules, Stats)>,
+}
+
+/// Safe autoplay only helps the player along and can be switched during a
+/// game, games with and without it count as the same rules.
+fn stats_rules(rules: &Rules) -> Rules {
+    Rules {
+        auto_play_safe: false,
+        ..*rules
+    }
+}
+
+impl PlayerStats {
+    /// The stats of the games played with `rules`.
+    pub fn get(&self, rules: &Rules) -> Option<&Stats> {
+        let rules = stats_rules(rules);
+        self.by_rules
+            .iter()
+            .find(|(played, _)| *played == rules)
+            .map(|(_, stats)| stats)
+    }
+
+    /// Counts `game` as a finished game with its rules, see `Stats::record`.
+    pub fn record(&mut self, game: &GameData) {
+        let rules = stats_rules(&game.rules);
+        let position = match self
+            .by_rules
+            .iter()
+            .position(|(played, _)| *played == rules)
+        {
+            Some(position) => position,
+            None => {
+                self.by_rules.push((rules, Stats::default()));
+                self.by_rules.len() - 1
+            }
+        };
+        self.by_rules[position].1.record(game);
+    }
+}
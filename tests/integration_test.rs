@@ -1,9 +1,14 @@
 mod utils;
 
-use crate::utils::get_game_data;
-use solitaire::{CardValue, GameAction, GameData};
+use crate::utils::{get_game_data, get_suit};
+use solitaire::{
+    Card, CardSymbol, CardValue, Clock, GameAction, GameData, GameStatus, MoveError, Pile, Rules,
+    Scoring, UndoPolicy, VEGAS_ANTE,
+};
+use std::time::Duration;
 
 #[test]
+#[allow(deprecated)]
 fn game_creation() {
     let game = GameData::new(10000);
 
@@ -39,6 +44,49 @@ fn game_creation() {
     assert!(!game.tables.playing_table[6][5].is_face_up);
 }
 
+#[test]
+fn seeded_game_creation() {
+    let game = GameData::new_with_seed(42);
+    let same_game = GameData::new_with_seed(42);
+    let other_game = GameData::new_with_seed(43);
+
+    assert_eq!(game.seed, 42);
+    assert_eq!(
+        format!("{:?}", game.tables),
+        format!("{:?}", same_game.tables)
+    );
+    assert_ne!(
+        format!("{:?}", game.tables),
+        format!("{:?}", other_game.tables)
+    );
+}
+
+#[test]
+fn seed_deals_a_known_game() {
+    // the deal of a seed must stay the same across versions, saved seeds and
+    // replays depend on it
+    let game = GameData::new_with_seed(1);
+
+    assert_eq!(
+        game.tables.to_string(),
+        "stock: #JD #6S #4S #AH #2C #4D #7C #JS #QD #2S #JH #5C #QH #5D #4H #JC #7D #6H #7S #2D #AC #KH #9C #3S
+waste:
+foundation:
+foundation:
+foundation:
+foundation:
+tableau: 8C
+tableau: #6C KD
+tableau: #3H #KS 5H
+tableau: #KC #TD #4C TS
+tableau: #5S #8D #2H #9H AD
+tableau: #6D #8H #AS #9D #3D QC
+tableau: #9S #TC #7H #QS #TH #8S 3C
+redeals: 0
+"
+    );
+}
+
 #[test]
 fn p2p_moves() {
     let mut game = get_game_data();
@@ -167,3 +215,1113 @@ fn e2p_illegal_move_empty() {
     game.do_(GameAction::MoveToPlayingFromDrawn(5))
         .expect("This should work");
 }
+
+#[test]
+fn move_error_reasons() {
+    let mut game = get_game_data();
+
+    assert_eq!(
+        game.do_(GameAction::MoveToPlayingFromPlaying(0, 1, 2, 1))
+            .err(),
+        Some(MoveError::WrongRank)
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToPlayingFromPlaying(0, 1, 0, 2))
+            .err(),
+        Some(MoveError::SameStack)
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToPlayingFromPlaying(0, 1, 4, 0))
+            .err(),
+        Some(MoveError::IndexOutOfBounds {
+            pile: Pile::Tableau(4),
+            index: 0
+        })
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToFoundationFromPlaying(4, 1))
+            .err(),
+        Some(MoveError::EmptyFoundationNeedsAce)
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToFoundationFromPlaying(6, 0))
+            .err(),
+        Some(MoveError::WrongSuit)
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToFoundationFromDrawn(0)).err(),
+        Some(MoveError::EmptySource)
+    );
+
+    game.do_(GameAction::DrawCard).expect("This should work");
+    assert_eq!(
+        game.do_(GameAction::MoveToPlayingFromDrawn(5)).err(),
+        Some(MoveError::SameColor)
+    );
+    assert_eq!(
+        game.do_(GameAction::MoveToPlayingFromDrawn(3)).err(),
+        Some(MoveError::EmptyTableauNeedsKing)
+    );
+}
+
+#[test]
+fn move_error_display() {
+    let error = MoveError::IndexOutOfBounds {
+        pile: Pile::Tableau(4),
+        index: 0,
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "There is no card with index 0 on the playing stack 4"
+    );
+    assert_eq!(
+        MoveError::FaceDownCard.to_string(),
+        "The card should be face up"
+    );
+}
+
+#[test]
+fn is_legal_does_not_change_tables() {
+    let game = get_game_data();
+    let before = format!("{:?}", game.tables);
+
+    assert!(game
+        .is_legal(&GameAction::MoveToPlayingFromPlaying(0, 1, 1, 1))
+        .is_ok());
+    assert_eq!(
+        game.is_legal(&GameAction::MoveToPlayingFromDrawn(5)),
+        Err(MoveError::EmptySource)
+    );
+    assert_eq!(
+        game.is_legal(&GameAction::MoveToFoundationFromPlaying(9, 0)),
+        Err(MoveError::NoSuchPile(Pile::Tableau(9)))
+    );
+    assert_eq!(
+        game.is_legal(&GameAction::MoveToFoundationFromDrawn(4)),
+        Err(MoveError::EmptySource)
+    );
+    assert_eq!(format!("{:?}", game.tables), before);
+}
+
+#[test]
+fn rejected_move_leaves_tables_untouched() {
+    let mut game = get_game_data();
+    game.do_(GameAction::DrawCard).expect("This should work");
+    let before = format!("{:?}", game.tables);
+
+    game.do_(GameAction::MoveToPlayingFromDrawn(5))
+        .expect_err("This should not work");
+    game.do_(GameAction::MoveToFoundationFromPlaying(4, 1))
+        .expect_err("This should not work");
+    game.do_(GameAction::MoveToPlayingFromFoundation(3, 0))
+        .expect_err("This should not work");
+
+    assert_eq!(format!("{:?}", game.tables), before);
+}
+
+#[test]
+fn undo_restores_tables() {
+    let mut game = get_game_data();
+    let before = format!("{:?}", game.tables);
+
+    let actions = [
+        GameAction::MoveToPlayingFromPlaying(0, 1, 1, 1),
+        GameAction::MoveToPlayingFromPlaying(2, 0, 3, 0),
+        GameAction::MoveToFoundationFromPlaying(4, 0),
+        GameAction::DrawCard,
+        GameAction::DrawCard,
+        GameAction::MoveToPlayingFromDrawn(5),
+    ];
+    for action in actions {
+        let action = game.do_(action).expect("This should work");
+        game.action_history.push(action);
+    }
+    for _ in actions {
+        game.undo();
+    }
+
+    assert_eq!(format!("{:?}", game.tables), before);
+}
+
+#[test]
+fn legal_moves_in_order() {
+    let game = get_game_data();
+
+    let moves: Vec<GameAction> = game.legal_moves().collect();
+    assert_eq!(
+        moves,
+        vec![
+            GameAction::DrawCard,
+            GameAction::MoveToFoundationFromPlaying(0, 1),
+            GameAction::MoveToFoundationFromPlaying(0, 2),
+            GameAction::MoveToFoundationFromPlaying(0, 3),
+            GameAction::MoveToFoundationFromPlaying(4, 0),
+            GameAction::MoveToFoundationFromPlaying(6, 1),
+            GameAction::MoveToFoundationFromPlaying(6, 2),
+            GameAction::MoveToFoundationFromPlaying(6, 3),
+            GameAction::MoveToPlayingFromPlaying(0, 1, 1, 1),
+            GameAction::MoveToPlayingFromPlaying(2, 0, 3, 0),
+            GameAction::MoveToPlayingFromPlaying(4, 0, 2, 1),
+        ]
+    );
+}
+
+#[test]
+fn legal_moves_are_legal() {
+    let mut game = GameData::new_with_seed(3);
+
+    for _ in 0..50 {
+        let moves: Vec<GameAction> = game.legal_moves().collect();
+        assert!(moves.iter().all(|action| game.is_legal(action).is_ok()));
+
+        // keep playing the last move so the search walks through the game
+        let action = *moves.last().expect("drawing is always possible here");
+        let action = game.do_(action).expect("This should work");
+        game.action_history.push(action);
+    }
+}
+
+/// Every action with stack, card and foundation numbers up to a bit past the
+/// table, to find the legal ones by trying them all.
+fn every_action() -> Vec<GameAction> {
+    let mut actions = vec![GameAction::DrawCard, GameAction::UnDraw];
+    for p in 0..8 {
+        actions.push(GameAction::MoveToPlayingFromDrawn(p));
+        actions.push(GameAction::MoveToDrawnFromPlaying(p));
+        actions.push(GameAction::ShowCard(p));
+        actions.push(GameAction::HideCard(p));
+        for f in 0..5 {
+            actions.push(GameAction::MoveToPlayingFromFoundation(p, f));
+            actions.push(GameAction::MoveToFoundationFromPlaying(p, f));
+        }
+        for fc in 0..20 {
+            for ts in 0..8 {
+                for tc in 0..20 {
+                    actions.push(GameAction::MoveToPlayingFromPlaying(p, fc, ts, tc));
+                }
+            }
+        }
+    }
+    for f in 0..5 {
+        actions.push(GameAction::MoveToDrawFromFoundation(f));
+        actions.push(GameAction::MoveToFoundationFromDrawn(f));
+    }
+    actions
+}
+
+/// Checks that `legal_moves` yields exactly the actions `is_legal` accepts.
+fn assert_legal_moves_complete(game: &GameData, actions: &[GameAction]) {
+    let legal: Vec<GameAction> = actions
+        .iter()
+        .copied()
+        .filter(|action| game.is_legal(action).is_ok())
+        .collect();
+    let mut moves: Vec<GameAction> = game.legal_moves().collect();
+    assert_eq!(moves.len(), legal.len());
+    moves.retain(|action| !legal.contains(action));
+    assert_eq!(moves, Vec::new());
+}
+
+#[test]
+fn legal_moves_are_every_legal_action() {
+    let actions = every_action();
+    assert_legal_moves_complete(&get_game_data(), &actions);
+
+    let mut game = GameData::new_with_seed(3);
+    for _ in 0..40 {
+        assert_legal_moves_complete(&game, &actions);
+        let action = game.hint().expect("This game has moves left");
+        let played = game.do_(action).expect("This should work");
+        game.action_history.push(played);
+    }
+}
+
+#[test]
+fn no_draw_with_empty_stock() {
+    let mut game = get_game_data();
+    game.tables.extra_table.clear();
+
+    assert_eq!(
+        game.is_legal(&GameAction::DrawCard),
+        Err(MoveError::EmptySource)
+    );
+    assert!(!game
+        .legal_moves()
+        .any(|action| action == GameAction::DrawCard));
+}
+
+#[test]
+fn status_in_progress() {
+    let game = get_game_data();
+
+    assert_eq!(game.status(), GameStatus::InProgress);
+    assert!(!game.is_won());
+}
+
+#[test]
+fn status_won() {
+    let mut game = get_game_data();
+    for stack in game.tables.playing_table.iter_mut() {
+        stack.clear();
+    }
+    game.tables.extra_table.clear();
+    game.tables.foundation_table = [
+        get_suit(CardSymbol::Clubs),
+        get_suit(CardSymbol::Diamonds),
+        get_suit(CardSymbol::Hearts),
+        get_suit(CardSymbol::Spades),
+    ];
+
+    assert_eq!(game.status(), GameStatus::Won);
+    assert!(game.is_won());
+}
+
+#[test]
+fn status_stuck() {
+    let mut game = get_game_data();
+    for stack in game.tables.playing_table.iter_mut() {
+        stack.clear();
+    }
+    game.tables.extra_table.clear();
+    game.tables.playing_table[0].push(Card {
+        value: CardValue::King,
+        symbol: CardSymbol::Clubs,
+        is_face_up: true,
+    });
+
+    // the king could move to an empty stack, but that gets the game nowhere
+    assert!(game.legal_moves().next().is_some());
+    assert_eq!(game.status(), GameStatus::Stuck);
+
+    // a queen somewhere in the stock can still be played after drawing
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Queen,
+        symbol: CardSymbol::Hearts,
+        is_face_up: true,
+    });
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Four,
+        symbol: CardSymbol::Spades,
+        is_face_up: true,
+    });
+    assert_eq!(game.status(), GameStatus::InProgress);
+}
+
+#[test]
+fn move_turns_uncovered_card() {
+    let mut game = get_game_data();
+    game.tables.playing_table[0][0].is_face_up = false;
+    let before = format!("{:?}", game.tables);
+
+    let played = game
+        .do_(GameAction::MoveToPlayingFromPlaying(0, 1, 1, 1))
+        .expect("This should work");
+    assert!(played.flipped);
+    assert!(game.tables.playing_table[0][0].is_face_up);
+    game.action_history.push(played);
+
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    assert!(!played.flipped);
+    game.action_history.push(played);
+    assert_eq!(game.move_count(), 2);
+
+    // a single undo per move, the turned card goes face down again
+    game.undo();
+    game.undo();
+    assert_eq!(format!("{:?}", game.tables), before);
+    assert_eq!(game.move_count(), 0);
+}
+
+#[test]
+fn take_back_actions_are_not_moves() {
+    let mut game = GameData::new_with_seed(1);
+    let before = format!("{:?}", game.tables);
+
+    for action in [
+        GameAction::UnDraw,
+        GameAction::MoveToDrawnFromPlaying(6),
+        GameAction::MoveToDrawFromFoundation(0),
+        GameAction::ShowCard(6),
+        GameAction::HideCard(6),
+    ] {
+        assert_eq!(game.is_legal(&action), Err(MoveError::TakeBackOnly));
+        assert_eq!(game.do_(action).err(), Some(MoveError::TakeBackOnly));
+    }
+    assert_eq!(format!("{:?}", game.tables), before);
+}
+
+#[test]
+fn undo_redo_restores_tables() {
+    let mut game = get_game_data();
+    game.tables.playing_table[0][0].is_face_up = false;
+
+    let actions = [
+        GameAction::MoveToPlayingFromPlaying(0, 1, 1, 1),
+        GameAction::DrawCard,
+        GameAction::MoveToFoundationFromPlaying(4, 0),
+    ];
+    let mut snapshots = vec![format!("{:?}", game.tables)];
+    for action in actions {
+        let played = game.do_(action).expect("This should work");
+        game.action_history.push(played);
+        snapshots.push(format!("{:?}", game.tables));
+    }
+
+    game.undo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[2]);
+    game.redo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[3]);
+
+    // the turned card is undone and redone together with its move
+    game.undo();
+    game.undo();
+    game.undo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[0]);
+    game.redo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[1]);
+    game.redo();
+    game.redo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[3]);
+
+    let history: Vec<GameAction> = game.action_history.iter().map(|m| m.action).collect();
+    assert_eq!(history, actions);
+
+    // there is nothing left to redo
+    game.redo();
+    assert_eq!(format!("{:?}", game.tables), snapshots[3]);
+}
+
+#[test]
+fn do_clears_redo() {
+    let mut game = get_game_data();
+
+    let action = game.do_(GameAction::DrawCard).expect("This should work");
+    game.action_history.push(action);
+    game.undo();
+    assert_eq!(game.redo_history.len(), 1);
+    assert_eq!(game.redo_history[0].action, GameAction::DrawCard);
+
+    let action = game
+        .do_(GameAction::MoveToFoundationFromPlaying(4, 0))
+        .expect("This should work");
+    game.action_history.push(action);
+    assert!(game.redo_history.is_empty());
+
+    game.redo();
+    assert_eq!(game.tables.drawn_table.len(), 0);
+}
+
+#[test]
+fn history_replays_exactly() {
+    let mut game = GameData::new_with_seed(11);
+    for turn in 0..80 {
+        let moves: Vec<GameAction> = game.legal_moves().collect();
+        let played = game
+            .do_(moves[turn % moves.len()])
+            .expect("This should work");
+        game.action_history.push(played);
+    }
+
+    let mut replay = GameData::new_with_seed(game.seed);
+    for played in game.action_history.iter() {
+        let replayed = replay.do_(played.action).expect("This should work");
+        assert_eq!(&replayed, played);
+        replay.action_history.push(replayed);
+    }
+
+    assert_eq!(format!("{:?}", replay.tables), format!("{:?}", game.tables));
+}
+
+#[test]
+fn draw_three_cards() {
+    let mut game = GameData::new_with_rules(5, Rules::draw_three());
+    let top_three: Vec<Card> = game.tables.extra_table[21..].to_vec();
+
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    assert_eq!(played.drawn, 3);
+    assert_eq!(game.tables.extra_table.len(), 21);
+    assert_eq!(game.tables.drawn_table.len(), 3);
+    assert!(game.tables.drawn_table.iter().all(|card| card.is_face_up));
+
+    // the top card of the stock is turned over first and ends up at the bottom
+    assert_eq!(
+        format!("{:?}", game.tables.drawn_table[0]),
+        format!(
+            "{:?}",
+            Card {
+                is_face_up: true,
+                ..top_three[2]
+            }
+        )
+    );
+    assert_eq!(
+        format!("{:?}", game.tables.drawn_table[2]),
+        format!(
+            "{:?}",
+            Card {
+                is_face_up: true,
+                ..top_three[0]
+            }
+        )
+    );
+}
+
+#[test]
+fn draw_three_undo_partial_draw() {
+    let mut game = get_game_data();
+    game.rules = Rules::draw_three();
+    game.tables.extra_table.insert(
+        0,
+        Card {
+            value: CardValue::Three,
+            symbol: CardSymbol::Hearts,
+            is_face_up: false,
+        },
+    );
+
+    let mut snapshots = vec![format!("{:?}", game.tables)];
+    for drawn in [3, 1, 0, 3] {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        assert_eq!(played.drawn, drawn);
+        game.action_history.push(played);
+        snapshots.push(format!("{:?}", game.tables));
+    }
+    assert_eq!(game.tables.extra_table.len(), 1);
+    assert_eq!(game.tables.drawn_table.len(), 3);
+
+    while let Some(snapshot) = snapshots.pop() {
+        assert_eq!(format!("{:?}", game.tables), snapshot);
+        game.undo();
+    }
+}
+
+#[test]
+fn redeal_limit() {
+    let mut game = get_game_data();
+    game.rules.redeal_limit = Some(1);
+
+    for _ in 0..3 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    assert!(game.can_redeal());
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    game.action_history.push(played);
+    assert_eq!(game.tables.redeals, 1);
+    assert!(!game.can_redeal());
+
+    for _ in 0..3 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    assert_eq!(
+        game.do_(GameAction::DrawCard).err(),
+        Some(MoveError::NoRedealsLeft)
+    );
+    assert!(!game
+        .legal_moves()
+        .any(|action| action == GameAction::DrawCard));
+
+    // undoing the last draws turns the stock back, freeing the redeal again
+    for _ in 0..4 {
+        game.undo();
+    }
+    assert_eq!(game.tables.redeals, 0);
+    assert!(game.can_redeal());
+    assert_eq!(game.tables.extra_table.len(), 0);
+    assert_eq!(game.tables.drawn_table.len(), 3);
+}
+
+#[test]
+fn undraw_cannot_corrupt_the_stock() {
+    // drawing three, an undo takes back exactly the three cards of the draw
+    let mut game = GameData::new_with_rules(1, Rules::draw_three());
+    for _ in 0..2 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    assert_eq!(
+        game.do_(GameAction::UnDraw).err(),
+        Some(MoveError::TakeBackOnly)
+    );
+    game.undo();
+    assert_eq!(game.tables.drawn_table.len(), 3);
+    assert_eq!(game.tables.extra_table.len(), 21);
+
+    // an undraw on a fresh deal cannot turn the stock over
+    let mut game = GameData::new_with_seed(1);
+    assert!(game.do_(GameAction::UnDraw).is_err());
+    game.undo();
+    assert_eq!(game.tables.redeals, 0);
+    assert_eq!(game.tables.extra_table.len(), 24);
+}
+
+#[test]
+fn tournament_rules() {
+    let mut game = GameData::new_with_rules(8, Rules::tournament(1));
+    for _ in 0..24 {
+        game.do_(GameAction::DrawCard).expect("This should work");
+    }
+
+    assert_eq!(
+        game.is_legal(&GameAction::DrawCard),
+        Err(MoveError::NoRedealsLeft)
+    );
+    assert_eq!(Rules::tournament(3).redeal_limit, Some(2));
+}
+
+#[test]
+fn stuck_without_redeals() {
+    let mut game = get_game_data();
+    for stack in game.tables.playing_table.iter_mut() {
+        stack.clear();
+    }
+    game.tables.extra_table.clear();
+    game.tables.playing_table[0].push(Card {
+        value: CardValue::King,
+        symbol: CardSymbol::Clubs,
+        is_face_up: true,
+    });
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Queen,
+        symbol: CardSymbol::Hearts,
+        is_face_up: true,
+    });
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Four,
+        symbol: CardSymbol::Spades,
+        is_face_up: true,
+    });
+    assert_eq!(game.status(), GameStatus::InProgress);
+
+    // the queen is buried in the drawn cards and the stock cannot be turned over
+    game.rules.redeal_limit = Some(0);
+    assert_eq!(game.status(), GameStatus::Stuck);
+}
+
+#[test]
+fn not_stuck_when_a_foundation_card_frees_a_face_down_card() {
+    let mut game = get_game_data();
+    game.tables = "foundation: AH 2H 3H 4H 5H
+foundation: AC 2C
+foundation: AD 2D 3D 4D 5D
+foundation:
+tableau: #AS 4C
+tableau: 6S
+tableau:
+tableau:
+tableau:
+tableau:
+tableau:
+"
+    .parse()
+    .expect("This should parse");
+
+    // 5H goes on 6S, then 4C on 5H turns the ace over
+    assert_eq!(game.status(), GameStatus::InProgress);
+
+    game.tables.playing_table[0][1].value = CardValue::Eight;
+    assert_eq!(game.status(), GameStatus::Stuck);
+}
+
+#[test]
+fn standard_scoring() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Standard;
+    game.tables.playing_table[0][0].is_face_up = false;
+
+    // the ace goes up and the two below it is turned over
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    assert_eq!(played.score, 15);
+    game.action_history.push(played);
+
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(4, 0))
+        .expect("This should work");
+    assert_eq!(played.score, 10);
+    game.action_history.push(played);
+
+    let played = game
+        .do_(GameAction::MoveToPlayingFromFoundation(4, 0))
+        .expect("This should work");
+    assert_eq!(played.score, -15);
+    game.action_history.push(played);
+
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Seven,
+        symbol: CardSymbol::Diamonds,
+        is_face_up: true,
+    });
+    let played = game
+        .do_(GameAction::MoveToPlayingFromDrawn(5))
+        .expect("This should work");
+    assert_eq!(played.score, 5);
+    game.action_history.push(played);
+    assert_eq!(game.score, 15);
+
+    game.undo();
+    game.undo();
+    assert_eq!(game.score, 25);
+    game.redo();
+    assert_eq!(game.score, 10);
+}
+
+#[test]
+fn standard_scoring_recycle() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Standard;
+    game.score = 30;
+    for _ in 0..3 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+
+    // turning the stock over costs 100 points, but the score stays at 0
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    assert_eq!(played.score, -30);
+    game.action_history.push(played);
+    assert_eq!(game.score, 0);
+
+    game.undo();
+    assert_eq!(game.score, 30);
+
+    // drawing three cards at a time turns the stock over for free
+    game.rules.draw_count = 3;
+    game.tables.extra_table.clear();
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    assert_eq!(played.score, 0);
+}
+
+#[test]
+fn no_scoring() {
+    let mut game = get_game_data();
+    game.tables.playing_table[0][0].is_face_up = false;
+
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    assert_eq!(played.score, 0);
+    assert_eq!(game.score, 0);
+}
+
+#[test]
+fn vegas_scoring() {
+    let game = GameData::new_with_rules(3, Rules::vegas(1, false));
+    assert_eq!(game.score, -VEGAS_ANTE);
+    assert_eq!(game.rules.max_redeals(), Some(0));
+
+    let mut game = get_game_data();
+    game.rules = Rules::vegas(3, true);
+    game.tables.playing_table[0][0].is_face_up = false;
+
+    // $5 a card on the foundations, nothing for turning cards over
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    assert_eq!(played.score, 5);
+    game.action_history.push(played);
+    let played = game
+        .do_(GameAction::MoveToPlayingFromFoundation(0, 1))
+        .expect("This should work");
+    assert_eq!(played.score, -5);
+    game.action_history.push(played);
+    assert_eq!(game.score, 0);
+
+    // the money is not clamped and the moves cannot be taken back
+    game.score = -VEGAS_ANTE;
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    game.action_history.push(played);
+    assert_eq!(game.score, 5 - VEGAS_ANTE);
+    assert!(!game.can_undo());
+    game.undo();
+    assert_eq!(game.move_count(), 3);
+    assert_eq!(game.score, 5 - VEGAS_ANTE);
+}
+
+#[test]
+fn vegas_limits_redeals() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Vegas { cumulative: false };
+    for _ in 0..3 {
+        game.do_(GameAction::DrawCard).expect("This should work");
+    }
+
+    assert_eq!(
+        game.is_legal(&GameAction::DrawCard),
+        Err(MoveError::NoRedealsLeft)
+    );
+
+    // a limit set in the rules still wins
+    game.rules.redeal_limit = Some(1);
+    assert!(game.can_redeal());
+}
+
+#[test]
+fn undo_penalty() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Standard;
+    game.rules.undo = UndoPolicy::Penalty(20);
+
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    game.action_history.push(played);
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(4, 0))
+        .expect("This should work");
+    game.action_history.push(played);
+    assert_eq!(game.score, 20);
+
+    // the penalty is not given back on redo, and the score stays at 0
+    game.undo();
+    assert_eq!(game.score, 0);
+    game.redo();
+    assert_eq!(game.score, 10);
+    game.undo();
+    assert_eq!(game.score, 0);
+    assert_eq!(game.move_count(), 1);
+
+    // without scoring there is nothing to pay
+    game.rules.scoring = Scoring::None;
+    game.score = 0;
+    game.redo();
+    game.undo();
+    assert_eq!(game.score, 0);
+}
+
+#[test]
+fn undo_and_redo_keep_standard_scores_above_zero() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Standard;
+    game.rules.undo = UndoPolicy::Penalty(20);
+    for action in [
+        GameAction::MoveToFoundationFromPlaying(0, 1),
+        GameAction::MoveToFoundationFromPlaying(4, 0),
+        GameAction::MoveToPlayingFromFoundation(4, 0),
+        GameAction::MoveToFoundationFromPlaying(4, 0),
+    ] {
+        let played = game.do_(action).expect("This should work");
+        game.action_history.push(played);
+    }
+    assert_eq!(game.score, 15);
+
+    // the penalties took the points the taken back moves had lost
+    game.undo();
+    game.undo();
+    assert_eq!(game.score, 0);
+    game.redo();
+    assert_eq!(game.score, 0);
+
+    // the time penalty took the points of the move
+    game.rules.undo = UndoPolicy::Allowed;
+    game.rules.timed = true;
+    game.redo();
+    assert_eq!(game.score, 10);
+    game.clock = Clock::paused_at(Duration::from_secs(50));
+    game.tick();
+    assert_eq!(game.score, 0);
+    game.undo();
+    assert_eq!(game.score, 0);
+}
+
+#[test]
+fn clock_starts_with_first_move() {
+    let mut game = get_game_data();
+    assert_eq!(game.clock.elapsed(), Duration::ZERO);
+    game.clock.pause();
+    assert!(!game.clock.is_paused());
+
+    game.do_(GameAction::DrawCard).expect("This should work");
+    std::thread::sleep(Duration::from_millis(20));
+    game.clock.pause();
+    let paused_at = game.clock.elapsed();
+    assert!(paused_at >= Duration::from_millis(20));
+    assert!(game.clock.is_paused());
+
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(game.clock.elapsed(), paused_at);
+    game.clock.resume();
+    assert!(!game.clock.is_paused());
+    assert!(game.clock.elapsed() >= paused_at);
+}
+
+#[test]
+fn timed_scoring() {
+    let mut game = get_game_data();
+    game.rules.scoring = Scoring::Standard;
+    game.rules.timed = true;
+    game.score = 100;
+    game.clock = Clock::paused_at(Duration::from_secs(35));
+
+    // 2 points for every 10 seconds, charged once
+    game.tick();
+    assert_eq!(game.score, 94);
+    game.tick();
+    assert_eq!(game.score, 94);
+
+    // the bonus for the win is part of the winning move
+    for stack in game.tables.playing_table.iter_mut() {
+        stack.clear();
+    }
+    game.tables.extra_table.clear();
+    let mut spades = get_suit(CardSymbol::Spades);
+    let king = spades.pop().unwrap();
+    game.tables.foundation_table = [
+        get_suit(CardSymbol::Clubs),
+        get_suit(CardSymbol::Diamonds),
+        get_suit(CardSymbol::Hearts),
+        spades,
+    ];
+    game.tables.drawn_table.push(king);
+    let played = game
+        .do_(GameAction::MoveToFoundationFromDrawn(3))
+        .expect("This should work");
+    assert_eq!(played.score, 10 + 700_000 / 35);
+    assert_eq!(game.score, 94 + 10 + 700_000 / 35);
+}
+
+#[test]
+fn hints_order() {
+    let game = get_game_data();
+
+    assert_eq!(
+        game.hints(),
+        vec![
+            GameAction::MoveToFoundationFromPlaying(0, 1),
+            GameAction::MoveToFoundationFromPlaying(0, 2),
+            GameAction::MoveToFoundationFromPlaying(0, 3),
+            GameAction::MoveToFoundationFromPlaying(4, 0),
+            GameAction::MoveToFoundationFromPlaying(6, 1),
+            GameAction::MoveToFoundationFromPlaying(6, 2),
+            GameAction::MoveToFoundationFromPlaying(6, 3),
+            GameAction::MoveToPlayingFromPlaying(4, 0, 2, 1),
+            GameAction::DrawCard,
+        ]
+    );
+    assert_eq!(
+        game.hint(),
+        Some(GameAction::MoveToFoundationFromPlaying(0, 1))
+    );
+}
+
+#[test]
+fn hints_uncover_and_make_room_for_kings() {
+    let mut game = get_game_data();
+    game.tables.foundation_table[0].clear();
+    game.tables.playing_table[0].clear();
+    game.tables.playing_table[6].clear();
+    game.tables.extra_table.clear();
+    game.tables.playing_table[4][0].is_face_up = false;
+
+    game.tables.playing_table[0].push(Card {
+        value: CardValue::Queen,
+        symbol: CardSymbol::Diamonds,
+        is_face_up: true,
+    });
+    game.tables.playing_table[6].push(Card {
+        value: CardValue::Three,
+        symbol: CardSymbol::Hearts,
+        is_face_up: true,
+    });
+    game.tables.drawn_table.push(Card {
+        value: CardValue::King,
+        symbol: CardSymbol::Hearts,
+        is_face_up: true,
+    });
+
+    // the jack uncovers the queen, the queen and the two of spades make room
+    // for the drawn king
+    assert_eq!(
+        game.hints(),
+        vec![
+            GameAction::MoveToPlayingFromPlaying(4, 1, 0, 1),
+            GameAction::MoveToPlayingFromPlaying(0, 0, 2, 1),
+            GameAction::MoveToPlayingFromPlaying(1, 0, 6, 1),
+            GameAction::MoveToPlayingFromDrawn(3),
+            GameAction::DrawCard,
+        ]
+    );
+}
+
+#[test]
+fn auto_complete() {
+    let mut game = get_game_data();
+    game.tables.extra_table.clear();
+    let mut clubs = get_suit(CardSymbol::Clubs);
+    let mut diamonds = get_suit(CardSymbol::Diamonds);
+    let mut hearts = get_suit(CardSymbol::Hearts);
+    let mut spades = get_suit(CardSymbol::Spades);
+    game.tables.playing_table = [
+        vec![clubs[12], hearts[11], spades[10]],
+        vec![hearts[12], clubs[11]],
+        vec![spades[12], diamonds[11], clubs[10]],
+        vec![diamonds[12], spades[11]],
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    ];
+    clubs.truncate(10);
+    diamonds.truncate(11);
+    hearts.truncate(11);
+    spades.truncate(10);
+    game.tables.foundation_table = [clubs, diamonds, hearts, spades];
+    let before = format!("{:?}", game.tables);
+    assert!(game.can_auto_complete());
+
+    assert_eq!(game.auto_complete(), 10);
+    assert!(game.is_won());
+    assert!(!game.can_auto_complete());
+    assert_eq!(game.move_count(), 10);
+
+    for _ in 0..10 {
+        game.undo();
+    }
+    assert_eq!(format!("{:?}", game.tables), before);
+}
+
+#[test]
+fn no_auto_complete_with_hidden_cards() {
+    let mut game = get_game_data();
+    game.tables.extra_table.clear();
+    assert!(game.can_auto_complete());
+
+    game.tables.playing_table[0][0].is_face_up = false;
+    assert!(!game.can_auto_complete());
+    game.tables.playing_table[0][0].is_face_up = true;
+    game.tables.drawn_table.push(Card {
+        value: CardValue::Six,
+        symbol: CardSymbol::Clubs,
+        is_face_up: true,
+    });
+    assert!(!game.can_auto_complete());
+}
+
+#[test]
+fn safe_autoplay() {
+    let mut game = get_game_data();
+    game.rules.auto_play_safe = true;
+    let before = format!("{:?}", game.tables);
+
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    let auto_actions: Vec<GameAction> = played.auto_moves.iter().map(|m| m.action).collect();
+    assert_eq!(
+        auto_actions,
+        vec![
+            GameAction::MoveToFoundationFromPlaying(6, 2),
+            GameAction::MoveToFoundationFromPlaying(1, 2),
+            GameAction::MoveToFoundationFromPlaying(6, 1),
+        ]
+    );
+    game.action_history.push(played);
+    let after = format!("{:?}", game.tables);
+
+    // the ten of diamonds is still needed for the black nine
+    assert_eq!(game.tables.foundation_table[0].len(), 1);
+    assert_eq!(game.tables.playing_table[4].len(), 3);
+
+    // a single undo takes back the whole gesture
+    assert_eq!(game.move_count(), 1);
+    game.undo();
+    assert_eq!(format!("{:?}", game.tables), before);
+    game.redo();
+    assert_eq!(format!("{:?}", game.tables), after);
+}
+
+/// The auto moves after a draw, with the clubs foundation up to `clubs`.
+fn auto_moves_with_clubs(clubs: &str) -> Vec<GameAction> {
+    let mut game = GameData::new_with_rules(
+        0,
+        Rules {
+            auto_play_safe: true,
+            ..Rules::default()
+        },
+    );
+    game.tables = format!(
+        "stock: KC
+         foundation: {}
+         foundation: AS 2S 3S
+         foundation: AD 2D 3D 4D
+         foundation: AH
+         tableau: 5D
+         tableau:
+         tableau:
+         tableau:
+         tableau:
+         tableau:
+         tableau:",
+        clubs
+    )
+    .parse()
+    .unwrap();
+
+    let played = game.do_(GameAction::DrawCard).expect("This should work");
+    played.auto_moves.iter().map(|m| m.action).collect()
+}
+
+#[test]
+fn autoplay_goes_up_to_two_above_the_other_color() {
+    // the five of diamonds may still be needed for the black four
+    assert_eq!(auto_moves_with_clubs("AC 2C"), Vec::new());
+    assert_eq!(
+        auto_moves_with_clubs("AC 2C 3C"),
+        vec![GameAction::MoveToFoundationFromPlaying(0, 2)]
+    );
+}
+
+#[test]
+fn no_autoplay_by_default() {
+    let mut game = get_game_data();
+
+    let played = game
+        .do_(GameAction::MoveToFoundationFromPlaying(0, 1))
+        .expect("This should work");
+    assert!(played.auto_moves.is_empty());
+    assert_eq!(game.tables.playing_table[6].len(), 2);
+}
+
+#[test]
+fn thoughtful_deal() {
+    let mut game = GameData::new_with_rules(5, Rules::thoughtful());
+    let all_face_up = |game: &GameData| {
+        let tables = &game.tables;
+        tables
+            .playing_table
+            .iter()
+            .chain([&tables.extra_table, &tables.drawn_table])
+            .all(|stack| stack.iter().all(|card| card.is_face_up))
+    };
+    assert!(all_face_up(&game));
+
+    // the stock stays face up when it is turned over and when draws are undone
+    for _ in 0..25 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    assert_eq!(game.tables.redeals, 1);
+    assert!(all_face_up(&game));
+    for _ in 0..3 {
+        game.undo();
+    }
+    assert!(all_face_up(&game));
+}
+
+#[test]
+fn thoughtful_runs_must_be_in_order() {
+    let game = GameData::new_with_rules(1, Rules::thoughtful());
+
+    // 2H would fit on 3C, but 9H and AD on top of it are no run
+    assert_eq!(
+        game.is_legal(&GameAction::MoveToPlayingFromPlaying(4, 2, 6, 7)),
+        Err(MoveError::SameColor)
+    );
+    assert!(!game
+        .legal_moves()
+        .any(|action| matches!(action, GameAction::MoveToPlayingFromPlaying(4, 2, _, _))));
+}
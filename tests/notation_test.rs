@@ -0,0 +1,121 @@
+mod utils;
+
+use crate::utils::get_game_data;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use solitaire::{
+    shuffled_deck, Card, CardSymbol, CardValue, GameAction, GameData, GameTables, ParseError,
+};
+
+#[test]
+fn card_notation() {
+    let ten: Card = "TD".parse().unwrap();
+    assert_eq!(ten.value, CardValue::Ten);
+    assert_eq!(ten.symbol, CardSymbol::Diamonds);
+    assert!(ten.is_face_up);
+    assert_eq!(ten.to_notation(), "TD");
+    assert_eq!("10D".parse::<Card>().unwrap().to_notation(), "TD");
+
+    let king: Card = "#KH".parse().unwrap();
+    assert!(!king.is_face_up);
+    assert_eq!(king.to_notation(), "#KH");
+
+    assert_eq!("Q".parse::<CardValue>(), Ok(CardValue::Queen));
+    assert_eq!("S".parse::<CardSymbol>(), Ok(CardSymbol::Spades));
+
+    for text in ["", "A", "#", "1C", "AX", "ac", "KH#", "AC "] {
+        assert_eq!(
+            text.parse::<Card>().err(),
+            Some(ParseError::Card(text.to_string())),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn every_card_round_trips() {
+    let deck = shuffled_deck(&mut ChaCha8Rng::seed_from_u64(0));
+    for card in deck {
+        for is_face_up in [true, false] {
+            let card = Card { is_face_up, ..card };
+            let read: Card = card.to_notation().parse().unwrap();
+            assert_eq!(format!("{:?}", read), format!("{:?}", card));
+        }
+    }
+}
+
+#[test]
+fn board_notation() {
+    let game = get_game_data();
+    assert_eq!(
+        game.tables.to_string(),
+        "stock: #6C #7H #5S\n\
+         waste:\n\
+         foundation: 9D\n\
+         foundation:\n\
+         foundation:\n\
+         foundation:\n\
+         tableau: 2C AD\n\
+         tableau: 2S\n\
+         tableau: KC\n\
+         tableau:\n\
+         tableau: QH JS TD\n\
+         tableau: 8S\n\
+         tableau: 2D AS\n\
+         redeals: 0\n"
+    );
+}
+
+#[test]
+fn board_round_trips() {
+    let mut game = GameData::new_with_seed(7);
+    for _ in 0..5 {
+        let played = game.do_(GameAction::DrawCard).unwrap();
+        game.action_history.push(played);
+    }
+
+    let read: GameTables = game.tables.to_string().parse().unwrap();
+    assert_eq!(format!("{:?}", read), format!("{:?}", game.tables));
+}
+
+#[test]
+fn board_errors() {
+    let tableau = "tableau:\n".repeat(7);
+    let foundations = "foundation:\n".repeat(4);
+
+    assert_eq!(
+        format!("{}{}", foundations, "tableau:\n".repeat(6))
+            .parse::<GameTables>()
+            .err(),
+        Some(ParseError::TableauCount(6))
+    );
+    assert_eq!(
+        format!("{}{}", "foundation:\n".repeat(5), tableau)
+            .parse::<GameTables>()
+            .err(),
+        Some(ParseError::FoundationCount(5))
+    );
+    assert_eq!(
+        format!("{}{}stock: AC XX", foundations, tableau)
+            .parse::<GameTables>()
+            .err(),
+        Some(ParseError::Card("XX".to_string()))
+    );
+    assert_eq!(
+        format!("{}{}stock:\nstock:", foundations, tableau)
+            .parse::<GameTables>()
+            .err(),
+        Some(ParseError::Line("stock:".to_string()))
+    );
+    assert_eq!(
+        format!("{}{}hand: AC", foundations, tableau)
+            .parse::<GameTables>()
+            .err(),
+        Some(ParseError::Line("hand: AC".to_string()))
+    );
+
+    let empty: GameTables = format!("{}{}", foundations, tableau).parse().unwrap();
+    assert!(empty.extra_table.is_empty() && empty.drawn_table.is_empty());
+    assert_eq!(empty.redeals, 0);
+}
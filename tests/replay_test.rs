@@ -0,0 +1,83 @@
+use solitaire::{GameAction, GameData, MoveError, Replay, Rules, Scoring};
+
+/// A game of a few moves with safe autoplay on, following the hints.
+fn played_game() -> GameData {
+    let mut game = GameData::new_with_rules(
+        7,
+        Rules {
+            scoring: Scoring::Standard,
+            auto_play_safe: true,
+            ..Rules::default()
+        },
+    );
+    for _ in 0..30 {
+        let action = game.hint().expect("This game has moves left");
+        let played = game.do_(action).expect("This should work");
+        game.action_history.push(played);
+    }
+    game
+}
+
+#[test]
+fn replay_rebuilds_the_game() {
+    let game = played_game();
+    let replay = game.to_replay();
+    assert_eq!(replay.seed, 7);
+    assert!(!replay.rules.auto_play_safe);
+
+    // the moves of safe autoplay are moves of their own
+    let auto_moves: usize = game
+        .action_history
+        .iter()
+        .map(|played| played.auto_moves.len())
+        .sum();
+    assert!(auto_moves > 0);
+    assert_eq!(replay.actions.len(), game.move_count() + auto_moves);
+
+    let replayed = replay
+        .game_at(replay.actions.len())
+        .expect("This should replay");
+    assert_eq!(
+        format!("{:?}", replayed.tables),
+        format!("{:?}", game.tables)
+    );
+    assert_eq!(replayed.score, game.score);
+
+    let dealt = replay.game_at(0).expect("This should replay");
+    let fresh = GameData::new_with_seed(7);
+    assert_eq!(format!("{:?}", dealt.tables), format!("{:?}", fresh.tables));
+}
+
+#[test]
+fn replay_steps_backward_with_undo() {
+    let replay = played_game().to_replay();
+    let mut game = replay
+        .game_at(replay.actions.len())
+        .expect("This should replay");
+
+    for step in (0..replay.actions.len()).rev() {
+        game.undo();
+        let expected = replay.game_at(step).expect("This should replay");
+        assert_eq!(
+            format!("{:?}", game.tables),
+            format!("{:?}", expected.tables)
+        );
+    }
+}
+
+#[test]
+fn replay_stops_at_an_illegal_move() {
+    let replay = Replay {
+        seed: 7,
+        rules: Rules::vegas(1, false),
+        actions: vec![
+            GameAction::DrawCard,
+            GameAction::MoveToPlayingFromFoundation(0, 0),
+        ],
+    };
+
+    assert_eq!(replay.game_at(2).err(), Some(MoveError::EmptySource));
+    // Vegas games cannot be undone, their replays can
+    let game = replay.game_at(1).expect("This should replay");
+    assert!(game.can_undo());
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "serde")]
+
+mod utils;
+
+use crate::utils::get_game_data;
+use solitaire::{
+    GameAction, GameData, LoadError, PlayerStats, Replay, Rules, Scoring, SAVE_VERSION,
+};
+
+#[test]
+fn save_and_load() {
+    let mut game = GameData::new_with_rules(
+        12,
+        Rules {
+            scoring: Scoring::Standard,
+            ..Rules::draw_three()
+        },
+    );
+    for _ in 0..4 {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    game.undo();
+
+    let mut loaded = GameData::from_json(&game.to_json()).expect("This should load");
+    assert_eq!(format!("{:?}", loaded.tables), format!("{:?}", game.tables));
+    assert_eq!(loaded.action_history, game.action_history);
+    assert_eq!(loaded.redo_history, game.redo_history);
+    assert_eq!(loaded.seed, 12);
+    assert_eq!(loaded.rules, game.rules);
+    assert_eq!(loaded.score, game.score);
+    assert!(loaded.clock.is_paused());
+    assert!(loaded.clock.elapsed() <= game.clock.elapsed());
+
+    // the history still works after loading
+    loaded.redo();
+    game.redo();
+    assert_eq!(format!("{:?}", loaded.tables), format!("{:?}", game.tables));
+    for _ in 0..4 {
+        loaded.undo();
+    }
+    let fresh = GameData::new_with_rules(12, Rules::draw_three());
+    assert_eq!(
+        format!("{:?}", loaded.tables),
+        format!("{:?}", fresh.tables)
+    );
+}
+
+#[test]
+fn unstarted_game_keeps_clock_stopped() {
+    let game = get_game_data();
+
+    let loaded = GameData::from_json(&game.to_json()).expect("This should load");
+    assert!(!loaded.clock.is_paused());
+}
+
+#[test]
+fn refuses_other_versions() {
+    let json = get_game_data().to_json().replacen(
+        &format!("\"version\": {}", SAVE_VERSION),
+        "\"version\": 999",
+        1,
+    );
+
+    assert!(matches!(
+        GameData::from_json(&json),
+        Err(LoadError::Version(999))
+    ));
+    assert!(matches!(
+        GameData::from_json("not a saved game"),
+        Err(LoadError::Json(_))
+    ));
+}
+
+#[test]
+fn replay_save_and_load() {
+    let mut game = GameData::new_with_rules(5, Rules::draw_three());
+    for action in [GameAction::DrawCard, GameAction::DrawCard] {
+        let played = game.do_(action).expect("This should work");
+        game.action_history.push(played);
+    }
+    let replay = game.to_replay();
+
+    let json = replay.to_json();
+    assert_eq!(Replay::from_json(&json).ok(), Some(replay));
+    assert!(matches!(
+        Replay::from_json(&json.replacen("\"version\": 1", "\"version\": 2", 1)),
+        Err(LoadError::Version(2))
+    ));
+}
+
+#[test]
+fn stats_save_and_load() {
+    let mut stats = PlayerStats::default();
+    stats.record(&get_game_data());
+    stats.record(&GameData::new_with_rules(3, Rules::vegas(3, false)));
+
+    assert_eq!(PlayerStats::from_json(&stats.to_json()).ok(), Some(stats));
+}
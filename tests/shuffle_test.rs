@@ -0,0 +1,103 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use solitaire::{shuffled_deck, Card};
+
+const DECK_SIZE: usize = 52;
+
+fn card_index(card: &Card) -> usize {
+    card.value as usize * 4 + card.symbol as usize
+}
+
+/// Counts how often every card lands on every position over `deals` shuffles.
+fn position_counts(deals: usize, seed: u64) -> Vec<[usize; DECK_SIZE]> {
+    let mut generator = ChaCha8Rng::seed_from_u64(seed);
+    let mut counts = vec![[0; DECK_SIZE]; DECK_SIZE];
+
+    for _ in 0..deals {
+        for (position, card) in shuffled_deck(&mut generator).iter().enumerate() {
+            counts[card_index(card)][position] += 1;
+        }
+    }
+
+    counts
+}
+
+fn chi_square(observed: &[usize], expected: f64) -> f64 {
+    observed
+        .iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+#[test]
+fn shuffle_is_a_permutation() {
+    let mut generator = ChaCha8Rng::seed_from_u64(7);
+
+    for _ in 0..100 {
+        let deck = shuffled_deck(&mut generator);
+        let mut seen = [false; DECK_SIZE];
+
+        assert_eq!(deck.len(), DECK_SIZE);
+        for card in deck.iter() {
+            assert!(!seen[card_index(card)], "{:?} was dealt twice", card);
+            seen[card_index(card)] = true;
+            assert!(!card.is_face_up);
+        }
+    }
+}
+
+#[test]
+fn shuffle_is_reproducible() {
+    let deck = shuffled_deck(&mut ChaCha8Rng::seed_from_u64(1234));
+    let same_deck = shuffled_deck(&mut ChaCha8Rng::seed_from_u64(1234));
+
+    assert_eq!(format!("{:?}", deck), format!("{:?}", same_deck));
+}
+
+#[test]
+fn chi_square_card_positions() {
+    // 200 expected hits per cell, the 99.9% quantile of chi-square with
+    // (52 - 1) * (52 - 1) = 2601 degrees of freedom is about 2830
+    let deals = DECK_SIZE * 200;
+    let counts = position_counts(deals, 2023);
+
+    let statistic: f64 = counts
+        .iter()
+        .map(|positions| chi_square(positions, 200.0))
+        .sum();
+
+    assert!(statistic < 2830.0, "chi-square statistic {}", statistic);
+}
+
+#[test]
+fn chi_square_each_card() {
+    // every card on its own should be spread evenly over the positions, the
+    // 99.9% quantile of chi-square with 51 degrees of freedom is about 88.0
+    let deals = DECK_SIZE * 200;
+    let counts = position_counts(deals, 99);
+    let mut failures = 0;
+
+    for positions in counts.iter() {
+        if chi_square(positions, 200.0) >= 88.0 {
+            failures += 1;
+        }
+    }
+
+    // with 52 independent-ish checks at p = 0.001 a single outlier is possible
+    assert!(failures <= 1, "{} cards are not spread evenly", failures);
+}
+
+#[test]
+fn chi_square_top_card() {
+    // the top card of the stock decides the first draw, check it on its own
+    let mut generator = ChaCha8Rng::seed_from_u64(5);
+    let mut counts = [0; DECK_SIZE];
+
+    for _ in 0..DECK_SIZE * 200 {
+        let deck = shuffled_deck(&mut generator);
+        counts[card_index(&deck[DECK_SIZE - 1])] += 1;
+    }
+
+    let statistic = chi_square(&counts, 200.0);
+    assert!(statistic < 88.0, "chi-square statistic {}", statistic);
+}
@@ -0,0 +1,143 @@
+mod utils;
+
+use crate::utils::{get_game_data, get_suit};
+use solitaire::{
+    solve, CardSymbol, DifficultyBand, GameData, Rules, Solution, SolverLimits, WINNABLE_LIMITS,
+};
+
+#[test]
+fn solves_winnable_deal() {
+    let mut game = GameData::new_with_rules(1, Rules::default());
+
+    let moves = match game.solve(&SolverLimits::default()) {
+        Solution::Winnable(moves) => moves,
+        other => panic!("seed 1 should be winnable, got {:?}", other),
+    };
+    for action in moves {
+        let played = game.do_(action).expect("The solution should be legal");
+        game.action_history.push(played);
+    }
+    assert!(game.is_won());
+}
+
+#[test]
+fn solves_draw_three() {
+    let game = GameData::new_with_rules(4, Rules::draw_three());
+
+    assert!(matches!(
+        game.solve(&SolverLimits::default()),
+        Solution::Winnable(_)
+    ));
+}
+
+#[test]
+fn solves_thoughtful() {
+    let mut game = GameData::new_with_rules(1, Rules::thoughtful());
+
+    let moves = match game.solve(&SolverLimits::default()) {
+        Solution::Winnable(moves) => moves,
+        other => panic!("seed 1 should be winnable, got {:?}", other),
+    };
+    for action in moves {
+        let played = game.do_(action).expect("The solution should be legal");
+        game.action_history.push(played);
+    }
+    assert!(game.is_won());
+}
+
+#[test]
+fn incomplete_deck_is_unwinnable() {
+    let game = get_game_data();
+
+    assert_eq!(
+        solve(&game.tables, &game.rules, &SolverLimits::default()),
+        Solution::Unwinnable
+    );
+}
+
+#[test]
+fn won_game_needs_no_moves() {
+    let mut game = get_game_data();
+    for stack in game.tables.playing_table.iter_mut() {
+        stack.clear();
+    }
+    game.tables.extra_table.clear();
+    game.tables.foundation_table = [
+        get_suit(CardSymbol::Clubs),
+        get_suit(CardSymbol::Diamonds),
+        get_suit(CardSymbol::Hearts),
+        get_suit(CardSymbol::Spades),
+    ];
+
+    assert_eq!(
+        game.solve(&SolverLimits::default()),
+        Solution::Winnable(Vec::new())
+    );
+}
+
+#[test]
+fn node_limit_gives_unknown() {
+    let game = GameData::new_with_rules(1, Rules::default());
+    let limits = SolverLimits {
+        max_nodes: 10,
+        ..SolverLimits::default()
+    };
+
+    assert_eq!(game.solve(&limits), Solution::Unknown);
+}
+
+#[test]
+fn state_hash_ignores_stack_order() {
+    let mut game = get_game_data();
+    let hash = game.state_hash();
+
+    game.tables.playing_table.swap(0, 3);
+    game.tables.playing_table.swap(1, 6);
+    game.tables.foundation_table.swap(0, 2);
+    assert_eq!(game.state_hash(), hash);
+
+    game.tables.playing_table[0].push(game.tables.extra_table.pop().unwrap());
+    assert_ne!(game.state_hash(), hash);
+}
+
+#[test]
+fn new_winnable_skips_dead_deals() {
+    let game = GameData::new_winnable(6, Rules::default());
+    assert_eq!(game.seed, 6);
+
+    // seed 7 is not solved within the budget
+    let game = GameData::new_winnable(7, Rules::default());
+    assert_eq!(game.seed, 8);
+    assert!(matches!(
+        game.solve(&WINNABLE_LIMITS),
+        Solution::Winnable(_)
+    ));
+}
+
+#[test]
+fn difficulty_of_solvable_deal() {
+    let game = GameData::new_with_rules(1, Rules::default());
+    let difficulty = game.difficulty();
+
+    assert_eq!(difficulty.solvable, Some(true));
+    assert_eq!(difficulty.first_solution_length, Some(121));
+    assert_eq!(difficulty.hidden_under_kings, 2);
+    assert!(difficulty.decisions > 0 && difficulty.decisions < 121);
+    assert_eq!(difficulty.band(), Some(DifficultyBand::Easy));
+
+    let game = GameData::new_with_difficulty(1, Rules::default(), DifficultyBand::Easy);
+    assert_eq!(game.seed, 1);
+}
+
+#[test]
+fn difficulty_of_unwinnable_deal() {
+    let mut game = get_game_data();
+    game.tables.playing_table[2].insert(0, game.tables.extra_table[0]);
+    game.tables.playing_table[2].insert(0, game.tables.extra_table[1]);
+    let difficulty = game.difficulty();
+
+    assert_eq!(difficulty.solvable, Some(false));
+    assert_eq!(difficulty.hidden_under_kings, 2);
+    assert_eq!(difficulty.score(), None);
+    assert_eq!(difficulty.band(), None);
+}
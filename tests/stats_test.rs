@@ -0,0 +1,91 @@
+mod utils;
+
+use crate::utils::get_suit;
+use solitaire::{CardSymbol, GameAction, GameData, PlayerStats, Rules, Scoring, Stats};
+
+/// A game with every card on the foundations after `moves` draws.
+fn won_game(rules: Rules, moves: usize) -> GameData {
+    let mut game = GameData::new_with_rules(1, rules);
+    for _ in 0..moves {
+        let played = game.do_(GameAction::DrawCard).expect("This should work");
+        game.action_history.push(played);
+    }
+    game.tables.playing_table = Default::default();
+    game.tables.extra_table.clear();
+    game.tables.drawn_table.clear();
+    game.tables.foundation_table = [
+        get_suit(CardSymbol::Clubs),
+        get_suit(CardSymbol::Diamonds),
+        get_suit(CardSymbol::Hearts),
+        get_suit(CardSymbol::Spades),
+    ];
+    game
+}
+
+#[test]
+fn record_wins_and_losses() {
+    let rules = Rules {
+        scoring: Scoring::Standard,
+        ..Rules::default()
+    };
+    let mut stats = Stats::default();
+    assert_eq!(stats.win_rate(), None);
+
+    stats.record(&won_game(rules, 5));
+    let mut best = won_game(rules, 3);
+    best.score = 40;
+    stats.record(&best);
+    stats.record(&GameData::new_with_rules(2, rules));
+    stats.record(&won_game(rules, 7));
+
+    assert_eq!(stats.played, 4);
+    assert_eq!(stats.won, 3);
+    assert_eq!(stats.win_rate(), Some(0.75));
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.best_streak, 2);
+    assert_eq!(stats.fewest_moves, Some(3));
+    assert!(stats.fastest_win.is_some());
+    assert_eq!(stats.best_score, Some(40));
+}
+
+#[test]
+fn no_best_score_without_scoring() {
+    let mut stats = Stats::default();
+    stats.record(&won_game(Rules::default(), 1));
+
+    assert_eq!(stats.best_score, None);
+    assert_eq!(stats.fewest_moves, Some(1));
+}
+
+#[test]
+fn stats_are_kept_by_rules() {
+    let mut stats = PlayerStats::default();
+    stats.record(&won_game(Rules::default(), 1));
+    stats.record(&GameData::new_with_rules(1, Rules::draw_three()));
+
+    // safe autoplay does not make other rules
+    let autoplay = Rules {
+        auto_play_safe: true,
+        ..Rules::default()
+    };
+    stats.record(&won_game(autoplay, 2));
+
+    assert_eq!(stats.by_rules.len(), 2);
+    assert_eq!(stats.get(&autoplay).map(|stats| stats.won), Some(2));
+    assert_eq!(
+        stats.get(&Rules::draw_three()).map(|stats| stats.won),
+        Some(0)
+    );
+    assert_eq!(stats.get(&Rules::thoughtful()), None);
+}
+
+#[test]
+fn cumulative_vegas_scores_count_per_game() {
+    let mut game = GameData::new_with_rules(1, Rules::vegas(1, true));
+    game.carried_score = 200;
+    game.score += game.carried_score;
+
+    let mut stats = Stats::default();
+    stats.record(&game);
+    assert_eq!(stats.best_score, Some(-52));
+}
@@ -1,96 +1,50 @@
-use solitaire::{Card, CardSymbol, CardValue, GameData, GameTables};
+use solitaire::{Card, CardSymbol, CardValue, GameData, Rules};
 
+#[allow(dead_code)] // not every test file uses it
 pub fn get_game_data() -> GameData {
-    GameData {
-        tables: GameTables {
-            playing_table: [
-                Vec::from([
-                    Card {
-                        value: CardValue::Two,
-                        symbol: CardSymbol::Clubs,
-                        is_face_up: true,
-                    },
-                    Card {
-                        value: CardValue::Ace,
-                        symbol: CardSymbol::Diamonds,
-                        is_face_up: true,
-                    },
-                ]),
-                Vec::from([Card {
-                    value: CardValue::Two,
-                    symbol: CardSymbol::Spades,
-                    is_face_up: true,
-                }]),
-                Vec::from([Card {
-                    value: CardValue::King,
-                    symbol: CardSymbol::Clubs,
-                    is_face_up: true,
-                }]),
-                Vec::new(),
-                Vec::from([
-                    Card {
-                        value: CardValue::Queen,
-                        symbol: CardSymbol::Hearts,
-                        is_face_up: true,
-                    },
-                    Card {
-                        value: CardValue::Jack,
-                        symbol: CardSymbol::Spades,
-                        is_face_up: true,
-                    },
-                    Card {
-                        value: CardValue::Ten,
-                        symbol: CardSymbol::Diamonds,
-                        is_face_up: true,
-                    },
-                ]),
-                Vec::from([Card {
-                    value: CardValue::Eight,
-                    symbol: CardSymbol::Spades,
-                    is_face_up: true,
-                }]),
-                Vec::from([
-                    Card {
-                        value: CardValue::Two,
-                        symbol: CardSymbol::Diamonds,
-                        is_face_up: true,
-                    },
-                    Card {
-                        value: CardValue::Ace,
-                        symbol: CardSymbol::Spades,
-                        is_face_up: true,
-                    },
-                ]),
-            ],
-            foundation_table: [
-                Vec::from([Card {
-                    value: CardValue::Nine,
-                    symbol: CardSymbol::Diamonds,
-                    is_face_up: true,
-                }]),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-            ],
-            drawn_table: Vec::new(),
-            extra_table: Vec::from([
-                Card {
-                    value: CardValue::Six,
-                    symbol: CardSymbol::Clubs,
-                    is_face_up: false,
-                },
-                Card {
-                    value: CardValue::Seven,
-                    symbol: CardSymbol::Hearts,
-                    is_face_up: false,
-                },
-                Card {
-                    value: CardValue::Five,
-                    symbol: CardSymbol::Spades,
-                    is_face_up: false,
-                },
-            ]),
-        },
-        action_history: Vec::new(),
-    }
+    let mut game = GameData::new_with_rules(0, Rules::default());
+    game.tables = "
+        stock: #6C #7H #5S
+        foundation: 9D
+        foundation:
+        foundation:
+        foundation:
+        tableau: 2C AD
+        tableau: 2S
+        tableau: KC
+        tableau:
+        tableau: QH JS TD
+        tableau: 8S
+        tableau: 2D AS
+    "
+    .parse()
+    .unwrap();
+    game
+}
+
+/// Every card of `symbol` from ace to king, face up.
+#[allow(dead_code)] // not every test file uses it
+pub fn get_suit(symbol: CardSymbol) -> Vec<Card> {
+    [
+        CardValue::Ace,
+        CardValue::Two,
+        CardValue::Three,
+        CardValue::Four,
+        CardValue::Five,
+        CardValue::Six,
+        CardValue::Seven,
+        CardValue::Eight,
+        CardValue::Nine,
+        CardValue::Ten,
+        CardValue::Jack,
+        CardValue::Queen,
+        CardValue::King,
+    ]
+    .into_iter()
+    .map(|value| Card {
+        value,
+        symbol,
+        is_face_up: true,
+    })
+    .collect()
 }